@@ -0,0 +1,396 @@
+//! Transcription Backend Module
+//!
+//! A backend starts worker processes that speak the whisper-engine JSON
+//! protocol: a `hello` handshake, then one job request per line on stdin,
+//! answered by progress, segment, complete and error messages on stdout.
+//! `SidecarManager` only sees the parsed messages, so the bundled
+//! whisper-engine, another engine such as a whisper.cpp wrapper, or a fake
+//! used in tests are interchangeable.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tauri_plugin_shell::process::{Command, CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::mpsc;
+
+use crate::diagnostics::ExitStatus;
+use crate::models::{AppError, BackendSettings, EngineRequest, SidecarMessage};
+
+/// Number of backend events buffered before a worker's output is throttled
+pub(crate) const EVENT_BUFFER: usize = 256;
+
+/// Longest output line buffered while waiting for its newline, in bytes
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// A line a worker wrote to stdout
+#[derive(Debug, Clone)]
+pub enum SidecarOutput {
+    Message(SidecarMessage),
+    /// A line that is not a valid protocol message
+    Invalid { line: String, error: AppError },
+}
+
+/// Something that happened in a backend worker
+#[derive(Debug, Clone)]
+pub enum BackendEvent {
+    Output(SidecarOutput),
+    /// A line the worker wrote to stderr
+    Stderr(String),
+    /// The worker's output could not be read
+    Error(String),
+    /// The worker exited; no events follow
+    Terminated(ExitStatus),
+}
+
+/// A running worker of a transcription backend
+pub trait BackendProcess: Send + Debug {
+    /// OS process ID, used to suspend the worker; `None` if it cannot be paused
+    fn pid(&self) -> Option<u32>;
+
+    /// Hand a job request to the worker
+    fn send(&mut self, request: &EngineRequest) -> Result<(), AppError>;
+
+    /// Stop the worker; a `Terminated` event follows
+    fn kill(self: Box<Self>) -> Result<(), AppError>;
+}
+
+/// Starts workers that transcribe jobs
+pub trait TranscriptionBackend: Send + Sync {
+    /// Name shown in diagnostics
+    fn name(&self) -> String;
+
+    /// Start a worker and return it with the stream of its events
+    fn spawn(&self) -> Result<(Box<dyn BackendProcess>, mpsc::Receiver<BackendEvent>), AppError>;
+}
+
+/// Create the backend selected in settings
+pub fn from_settings(app: AppHandle, settings: &BackendSettings) -> Arc<dyn TranscriptionBackend> {
+    match settings {
+        BackendSettings::WhisperEngine => Arc::new(WhisperEngineBackend { app }),
+        BackendSettings::ExternalCommand { program, args } => Arc::new(ExternalCommandBackend {
+            app,
+            program: program.clone(),
+            args: args.clone(),
+        }),
+    }
+}
+
+// ============================================
+// Process Backends
+// ============================================
+
+/// The whisper-engine sidecar bundled with the app, run in worker mode
+pub struct WhisperEngineBackend {
+    app: AppHandle,
+}
+
+impl TranscriptionBackend for WhisperEngineBackend {
+    fn name(&self) -> String {
+        "whisper-engine".to_string()
+    }
+
+    fn spawn(&self) -> Result<(Box<dyn BackendProcess>, mpsc::Receiver<BackendEvent>), AppError> {
+        let command = self
+            .app
+            .shell()
+            .sidecar("whisper-engine")
+            .map_err(|e| AppError::SidecarError(format!("Failed to create sidecar command: {}", e)))?
+            .args(["--worker"]);
+        spawn_process(command)
+    }
+}
+
+/// Any program that speaks the worker protocol, configured in settings
+pub struct ExternalCommandBackend {
+    app: AppHandle,
+    program: String,
+    args: Vec<String>,
+}
+
+impl TranscriptionBackend for ExternalCommandBackend {
+    fn name(&self) -> String {
+        self.program.clone()
+    }
+
+    fn spawn(&self) -> Result<(Box<dyn BackendProcess>, mpsc::Receiver<BackendEvent>), AppError> {
+        if self.program.trim().is_empty() {
+            return Err(AppError::InvalidInput("No transcription command configured".to_string()));
+        }
+        let command = self.app.shell().command(&self.program).args(&self.args);
+        spawn_process(command)
+    }
+}
+
+/// A worker running as a child process
+#[derive(Debug)]
+struct ProcessWorker {
+    child: CommandChild,
+}
+
+impl BackendProcess for ProcessWorker {
+    fn pid(&self) -> Option<u32> {
+        Some(self.child.pid())
+    }
+
+    fn send(&mut self, request: &EngineRequest) -> Result<(), AppError> {
+        let mut line = serde_json::to_vec(request)
+            .map_err(|e| AppError::SidecarError(format!("Failed to encode job request: {}", e)))?;
+        line.push(b'\n');
+        self.child
+            .write(&line)
+            .map_err(|e| AppError::SidecarError(format!("Failed to send job to worker: {}", e)))
+    }
+
+    fn kill(self: Box<Self>) -> Result<(), AppError> {
+        self.child
+            .kill()
+            .map_err(|e| AppError::SidecarError(format!("Failed to kill process: {}", e)))
+    }
+}
+
+/// Spawn a worker command and turn its raw output into backend events
+fn spawn_process(command: Command) -> Result<(Box<dyn BackendProcess>, mpsc::Receiver<BackendEvent>), AppError> {
+    let (mut rx, child) = command
+        .set_raw_out(true)
+        .spawn()
+        .map_err(|e| AppError::SidecarError(format!("Failed to spawn sidecar: {}", e)))?;
+
+    let (tx, events) = mpsc::channel(EVENT_BUFFER);
+
+    tauri::async_runtime::spawn(async move {
+        let mut stdout = LineBuffer::new();
+        let mut stderr = LineBuffer::new();
+
+        while let Some(event) = rx.recv().await {
+            let mut forwarded = Vec::new();
+            match event {
+                CommandEvent::Stdout(chunk) => {
+                    forwarded.extend(stdout.push(&chunk).into_iter().filter_map(parse_output));
+                }
+                CommandEvent::Stderr(chunk) => {
+                    forwarded.extend(stderr.push(&chunk).into_iter().map(BackendEvent::Stderr));
+                }
+                CommandEvent::Error(error) => forwarded.push(BackendEvent::Error(error)),
+                CommandEvent::Terminated(payload) => {
+                    // A last line without a trailing newline still counts
+                    forwarded.extend(stdout.finish().and_then(parse_output));
+                    forwarded.extend(stderr.finish().map(BackendEvent::Stderr));
+                    forwarded.push(BackendEvent::Terminated(ExitStatus {
+                        code: payload.code,
+                        signal: payload.signal,
+                    }));
+                }
+                _ => {}
+            }
+
+            for event in forwarded {
+                if tx.send(event).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    Ok((Box::new(ProcessWorker { child }), events))
+}
+
+// ============================================
+// Output Parsing
+// ============================================
+
+/// Reassembles output chunks into complete lines
+///
+/// Chunks can end in the middle of a line, or even in the middle of a UTF-8
+/// character, and one chunk can hold several lines.
+#[derive(Debug, Default)]
+struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a chunk of output and take the lines it completes
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        while let Some(newline) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=newline).collect();
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+
+        // Never buffer without bound; an overlong line is handed on and fails to parse
+        if self.pending.len() > MAX_LINE_BYTES {
+            lines.extend(self.finish());
+        }
+
+        lines
+    }
+
+    /// Take whatever is left after the process exited
+    fn finish(&mut self) -> Option<String> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let rest = std::mem::take(&mut self.pending);
+        Some(String::from_utf8_lossy(&rest).into_owned())
+    }
+}
+
+/// Parse a line of output from the sidecar process
+///
+/// Returns `None` for blank lines.
+fn parse_sidecar_line(line: &str) -> Result<Option<SidecarMessage>, AppError> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::from_str(line)
+        .map(Some)
+        .map_err(|e| AppError::SidecarError(format!("Failed to parse sidecar output: {} - Line: {}", e, line)))
+}
+
+/// Turn a stdout line into an output event; blank lines are dropped
+pub(crate) fn parse_output(line: String) -> Option<BackendEvent> {
+    let output = match parse_sidecar_line(&line) {
+        Ok(Some(message)) => SidecarOutput::Message(message),
+        Ok(None) => return None,
+        Err(error) => SidecarOutput::Invalid {
+            line: line.trim_end().to_string(),
+            error,
+        },
+    };
+    Some(BackendEvent::Output(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TranscriptionTask;
+
+    #[test]
+    fn test_parse_sidecar_line() {
+        assert!(parse_sidecar_line("   ").unwrap().is_none());
+        assert!(parse_sidecar_line("not json").is_err());
+
+        let message = parse_sidecar_line(r#"{"type":"complete","language":"tr","duration":12.5}"#)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(message, SidecarMessage::Complete { ref language, .. } if language == "tr"));
+    }
+
+    #[test]
+    fn test_parse_output_keeps_invalid_lines() {
+        assert!(parse_output("\r\n".to_string()).is_none());
+
+        match parse_output("Downloading model...\n".to_string()) {
+            Some(BackendEvent::Output(SidecarOutput::Invalid { line, .. })) => {
+                assert_eq!(line, "Downloading model...");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    /// Backend whose workers answer every request with a complete message
+    struct EchoBackend;
+
+    #[derive(Debug)]
+    struct EchoProcess {
+        events: mpsc::Sender<BackendEvent>,
+    }
+
+    impl BackendProcess for EchoProcess {
+        fn pid(&self) -> Option<u32> {
+            None
+        }
+
+        fn send(&mut self, request: &EngineRequest) -> Result<(), AppError> {
+            let EngineRequest::Transcribe { start_offset, .. } = request;
+            let message = SidecarMessage::Complete { language: "en".to_string(), duration: *start_offset };
+            self.events
+                .try_send(BackendEvent::Output(SidecarOutput::Message(message)))
+                .map_err(|e| AppError::SidecarError(e.to_string()))
+        }
+
+        fn kill(self: Box<Self>) -> Result<(), AppError> {
+            self.events
+                .try_send(BackendEvent::Terminated(ExitStatus { code: None, signal: Some(9) }))
+                .map_err(|e| AppError::SidecarError(e.to_string()))
+        }
+    }
+
+    impl TranscriptionBackend for EchoBackend {
+        fn name(&self) -> String {
+            "echo".to_string()
+        }
+
+        fn spawn(&self) -> Result<(Box<dyn BackendProcess>, mpsc::Receiver<BackendEvent>), AppError> {
+            let (events, rx) = mpsc::channel(EVENT_BUFFER);
+            Ok((Box::new(EchoProcess { events }), rx))
+        }
+    }
+
+    #[test]
+    fn test_backend_can_be_replaced() {
+        let backend: Arc<dyn TranscriptionBackend> = Arc::new(EchoBackend);
+        let (mut process, mut events) = backend.spawn().unwrap();
+
+        process
+            .send(&EngineRequest::Transcribe {
+                job_id: "job-1".to_string(),
+                audio_path: "/in/a.mp3".to_string(),
+                model: "base".to_string(),
+                start_offset: 4.0,
+                language: None,
+                task: TranscriptionTask::Transcribe,
+                initial_prompt: None,
+                temperature: 0.0,
+                device: None,
+                threads: None,
+            })
+            .unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            BackendEvent::Output(SidecarOutput::Message(SidecarMessage::Complete { duration, .. })) if duration == 4.0
+        ));
+
+        process.kill().unwrap();
+        assert!(matches!(events.try_recv().unwrap(), BackendEvent::Terminated(ExitStatus { signal: Some(9), .. })));
+    }
+
+    #[test]
+    fn test_line_buffer_reassembles_split_chunks() {
+        let mut buffer = LineBuffer::new();
+        assert!(buffer.push(br#"{"type":"progress","#).is_empty());
+
+        let lines = buffer.push(b"\"percent\":5,\"status\":\"a\"}\n{\"type\":\"err");
+        assert_eq!(lines, vec!["{\"type\":\"progress\",\"percent\":5,\"status\":\"a\"}\n"]);
+
+        let lines = buffer.push(b"or\",\"message\":\"x\"}\r\n\n");
+        assert_eq!(lines.len(), 2);
+        assert!(matches!(
+            parse_sidecar_line(&lines[0]).unwrap(),
+            Some(SidecarMessage::Error { ref message }) if message == "x"
+        ));
+        assert!(parse_sidecar_line(&lines[1]).unwrap().is_none());
+
+        assert!(buffer.finish().is_none());
+        buffer.push(br#"{"type":"complete","language":"en","duration":1.0}"#);
+        assert!(parse_sidecar_line(&buffer.finish().unwrap()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_line_buffer_keeps_split_utf8_characters() {
+        let text = "{\"text\":\"dünya\"}\n".as_bytes();
+        let split = text.iter().position(|&b| b == 0xC3).unwrap() + 1;
+
+        let mut buffer = LineBuffer::new();
+        assert!(buffer.push(&text[..split]).is_empty());
+        assert_eq!(buffer.push(&text[split..]), vec!["{\"text\":\"dünya\"}\n"]);
+    }
+}
@@ -0,0 +1,231 @@
+//! Sidecar Failure Diagnostics Module
+//!
+//! Keeps a bounded tail of each job's sidecar stderr and turns a failed run
+//! into a specific `AppError`, so the frontend can show something more useful
+//! than an exit code.
+
+use std::collections::VecDeque;
+
+use crate::models::AppError;
+
+/// Number of stderr lines kept per job
+pub const STDERR_TAIL_LINES: usize = 40;
+
+/// Longest stderr line kept, in characters; longer lines are cut
+const MAX_LINE_CHARS: usize = 500;
+
+/// Bounded ring buffer of the most recent stderr lines of a sidecar
+#[derive(Debug, Clone, Default)]
+pub struct StderrTail {
+    lines: VecDeque<String>,
+}
+
+impl StderrTail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a chunk of stderr output, which may contain several lines
+    pub fn push(&mut self, chunk: &str) {
+        for line in chunk.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            if self.lines.len() == STDERR_TAIL_LINES {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line.chars().take(MAX_LINE_CHARS).collect());
+        }
+    }
+
+    /// Get the buffered lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+}
+
+/// How a sidecar process ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitStatus {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+}
+
+/// Windows `STATUS_NO_MEMORY` exit code
+const STATUS_NO_MEMORY: i32 = 0xC000_0017_u32 as i32;
+
+/// Unix `SIGKILL`, also used by the Linux OOM killer
+const SIGKILL: i32 = 9;
+
+const MODEL_PATTERNS: &[&str] = &[
+    "model not found",
+    "no such model",
+    "invalid model",
+    "model file not found",
+    "checkpoint not found",
+    "failed to download model",
+    "unable to download",
+    "not a valid model",
+];
+
+const MEMORY_PATTERNS: &[&str] = &[
+    "out of memory",
+    "memoryerror",
+    "cannot allocate memory",
+    "std::bad_alloc",
+    "failed to allocate",
+    "not enough memory",
+];
+
+const DECODE_PATTERNS: &[&str] = &[
+    "invalid data found when processing input",
+    "failed to load audio",
+    "could not decode",
+    "error opening input",
+    "moov atom not found",
+    "could not determine audio duration",
+    "does not contain any stream",
+];
+
+/// Turn a failed sidecar run into a specific error
+///
+/// `engine_message` is the text of an `error` protocol message, if the engine
+/// sent one before exiting. The engine message and stderr tail are searched
+/// for known failure signatures; the exit status decides the rest.
+pub fn classify_failure(
+    exit: Option<ExitStatus>,
+    engine_message: Option<&str>,
+    stderr: &[String],
+) -> AppError {
+    let candidates: Vec<&str> = engine_message
+        .into_iter()
+        .chain(stderr.iter().rev().map(String::as_str))
+        .collect();
+    let find = |patterns: &[&str]| {
+        candidates.iter().find(|line| {
+            let lower = line.to_lowercase();
+            patterns.iter().any(|p| lower.contains(p))
+        })
+    };
+
+    if let Some(line) = find(MEMORY_PATTERNS) {
+        return AppError::OutOfMemory(line.to_string());
+    }
+    if exit.and_then(|e| e.code) == Some(STATUS_NO_MEMORY) {
+        return AppError::OutOfMemory("Process ran out of memory".to_string());
+    }
+    if let Some(line) = find(MODEL_PATTERNS) {
+        return AppError::ModelNotFound(line.to_string());
+    }
+    if let Some(line) = find(DECODE_PATTERNS) {
+        return AppError::DecodeFailed(line.to_string());
+    }
+
+    if let Some(signal) = exit.and_then(|e| e.signal) {
+        let detail = if signal == SIGKILL {
+            format!("signal {} (possibly out of memory)", signal)
+        } else {
+            format!("signal {}", signal)
+        };
+        return AppError::ProcessKilled(detail);
+    }
+
+    match (engine_message, exit.and_then(|e| e.code)) {
+        (Some(message), _) => AppError::TranscriptionFailed(message.to_string()),
+        (None, Some(code)) => AppError::TranscriptionFailed(format!("Process exited with code: {}", code)),
+        (None, None) => AppError::TranscriptionFailed("Process exited unexpectedly".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn exited(code: i32) -> Option<ExitStatus> {
+        Some(ExitStatus { code: Some(code), signal: None })
+    }
+
+    #[test]
+    fn test_stderr_tail_is_bounded() {
+        let mut tail = StderrTail::new();
+        for i in 0..(STDERR_TAIL_LINES + 10) {
+            tail.push(&format!("line {}\n", i));
+        }
+
+        let buffered = tail.lines();
+        assert_eq!(buffered.len(), STDERR_TAIL_LINES);
+        assert_eq!(buffered[0], "line 10");
+        assert_eq!(buffered.last().unwrap(), &format!("line {}", STDERR_TAIL_LINES + 9));
+    }
+
+    #[test]
+    fn test_stderr_tail_splits_chunks_and_cuts_long_lines() {
+        let mut tail = StderrTail::new();
+        tail.push("first\n\nsecond\r\n");
+        tail.push(&"x".repeat(MAX_LINE_CHARS * 2));
+
+        let buffered = tail.lines();
+        assert_eq!(buffered.len(), 3);
+        assert_eq!(buffered[0], "first");
+        assert_eq!(buffered[1], "second");
+        assert_eq!(buffered[2].len(), MAX_LINE_CHARS);
+
+        tail.clear();
+        assert!(tail.lines().is_empty());
+    }
+
+    #[test]
+    fn test_classify_missing_model() {
+        let error = classify_failure(exited(1), Some("Model not found: large-v9"), &[]);
+        assert!(matches!(error, AppError::ModelNotFound(ref m) if m.contains("large-v9")));
+    }
+
+    #[test]
+    fn test_classify_out_of_memory() {
+        let stderr = lines(&[
+            "Traceback (most recent call last):",
+            "torch.cuda.OutOfMemoryError: CUDA out of memory. Tried to allocate 2.00 GiB",
+        ]);
+        let error = classify_failure(exited(1), Some("CUDA out of memory"), &stderr);
+        assert!(matches!(error, AppError::OutOfMemory(_)));
+
+        let error = classify_failure(exited(STATUS_NO_MEMORY), None, &[]);
+        assert!(matches!(error, AppError::OutOfMemory(_)));
+    }
+
+    #[test]
+    fn test_classify_decode_failure() {
+        let stderr = lines(&["[mov,mp4,m4a] moov atom not found", "input.mp4: Invalid data found when processing input"]);
+        let error = classify_failure(exited(1), None, &stderr);
+        assert!(matches!(error, AppError::DecodeFailed(ref m) if m.contains("Invalid data")));
+    }
+
+    #[test]
+    fn test_classify_killed_by_signal() {
+        let exit = Some(ExitStatus { code: None, signal: Some(SIGKILL) });
+        let error = classify_failure(exit, None, &lines(&["loading model"]));
+        assert!(matches!(error, AppError::ProcessKilled(ref m) if m.contains("out of memory")));
+
+        let exit = Some(ExitStatus { code: None, signal: Some(11) });
+        assert!(matches!(classify_failure(exit, None, &[]), AppError::ProcessKilled(_)));
+    }
+
+    #[test]
+    fn test_classify_falls_back_to_exit_code() {
+        let error = classify_failure(exited(3), None, &lines(&["something odd"]));
+        assert_eq!(error.to_string(), "Transcription failed: Process exited with code: 3");
+
+        let error = classify_failure(exited(1), Some("Unexpected token"), &[]);
+        assert!(matches!(error, AppError::TranscriptionFailed(ref m) if m == "Unexpected token"));
+    }
+}
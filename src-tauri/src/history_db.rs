@@ -0,0 +1,542 @@
+//! Transcript History Database
+//!
+//! Keeps the transcript history in an embedded SQLite database, so the
+//! history list is a single indexed query instead of a file holding every
+//! entry. Each transcript is stored as its versioned JSON document, the same
+//! as in a transcript file, next to the columns the history list needs; its
+//! segments get a row each, indexed by an FTS5 table for full-text search
+//! with timestamps.
+
+use crate::models::{AppError, HistoryItem, StoredTranscript};
+use crate::storage::{decode_transcript, encode_transcript, SearchResult, SegmentHit, StorageReport};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Version of the database schema, stored as `PRAGMA user_version`
+const SCHEMA_VERSION: i32 = 1;
+
+/// Most segment hits a search returns across all transcripts
+const MAX_SEARCH_HITS: usize = 500;
+
+/// Marker in the `meta` table set once the JSON history has been imported
+const JSON_IMPORTED_KEY: &str = "json_imported";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS transcripts (
+        id TEXT PRIMARY KEY,
+        file_name TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        duration REAL NOT NULL,
+        language TEXT NOT NULL,
+        document TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS transcripts_created_at ON transcripts (created_at DESC);
+    CREATE TABLE IF NOT EXISTS segments (
+        id INTEGER PRIMARY KEY,
+        transcript_id TEXT NOT NULL REFERENCES transcripts (id) ON DELETE CASCADE,
+        segment_id TEXT NOT NULL,
+        start_time REAL NOT NULL,
+        end_time REAL NOT NULL,
+        text TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS segments_transcript_id ON segments (transcript_id);
+    CREATE VIRTUAL TABLE IF NOT EXISTS segments_fts USING fts5 (
+        text,
+        content = 'segments',
+        content_rowid = 'id',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER IF NOT EXISTS segments_after_insert AFTER INSERT ON segments BEGIN
+        INSERT INTO segments_fts (rowid, text) VALUES (new.id, new.text);
+    END;
+    CREATE TRIGGER IF NOT EXISTS segments_after_delete AFTER DELETE ON segments BEGIN
+        INSERT INTO segments_fts (segments_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    END;
+    CREATE TRIGGER IF NOT EXISTS segments_after_update AFTER UPDATE ON segments BEGIN
+        INSERT INTO segments_fts (segments_fts, rowid, text) VALUES ('delete', old.id, old.text);
+        INSERT INTO segments_fts (rowid, text) VALUES (new.id, new.text);
+    END;
+";
+
+/// Transcript history stored in a SQLite database
+pub struct HistoryDb {
+    /// The connection is not `Sync`; the lock also serializes writers
+    conn: Mutex<Connection>,
+}
+
+impl HistoryDb {
+    /// Open the database at `path`, creating it and its schema if needed
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::StorageError(format!("Failed to create database directory: {}", e)))?;
+        }
+
+        let conn = Connection::open(path).map_err(db_error("Failed to open history database"))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(db_error("Failed to configure history database"))?;
+        conn.pragma_update(None, "foreign_keys", true)
+            .map_err(db_error("Failed to configure history database"))?;
+
+        let version: i32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(db_error("Failed to read history database version"))?;
+        if version > SCHEMA_VERSION {
+            return Err(AppError::StorageError(format!(
+                "History database version {} is newer than supported version {}",
+                version, SCHEMA_VERSION
+            )));
+        }
+        conn.execute_batch(SCHEMA).map_err(db_error("Failed to create history database"))?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+            .map_err(db_error("Failed to write history database version"))?;
+
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Whether the JSON history has already been imported
+    pub fn json_imported(&self) -> Result<bool, AppError> {
+        self.conn()
+            .query_row("SELECT 1 FROM meta WHERE key = ?1", [JSON_IMPORTED_KEY], |_| Ok(()))
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(db_error("Failed to read history database"))
+    }
+
+    /// Import transcripts from the JSON history
+    ///
+    /// The transcripts and the import marker are committed together, so an
+    /// interrupted import is retried from scratch on the next start.
+    /// Transcripts already in the database are replaced.
+    pub fn import_json(&self, transcripts: &[StoredTranscript]) -> Result<(), AppError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(db_error("Failed to import history"))?;
+        for transcript in transcripts {
+            insert_transcript(&tx, transcript)?;
+        }
+        tx.execute("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)", params![JSON_IMPORTED_KEY, crate::storage::current_timestamp()])
+            .map_err(db_error("Failed to import history"))?;
+        tx.commit().map_err(db_error("Failed to import history"))
+    }
+
+    /// Save a transcript, replacing one with the same ID
+    pub fn save(&self, transcript: &StoredTranscript) -> Result<(), AppError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(db_error("Failed to save transcript"))?;
+        insert_transcript(&tx, transcript)?;
+        tx.commit().map_err(db_error("Failed to save transcript"))
+    }
+
+    /// Load a transcript by ID
+    pub fn load(&self, id: &str) -> Result<StoredTranscript, AppError> {
+        let document: String = self
+            .conn()
+            .query_row("SELECT document FROM transcripts WHERE id = ?1", [id], |row| row.get(0))
+            .optional()
+            .map_err(db_error("Failed to read transcript"))?
+            .ok_or_else(|| AppError::StorageError(format!("Transcript not found: {}", id)))?;
+
+        decode_transcript(&document)
+    }
+
+    /// Delete a transcript and its segments; unknown IDs are ignored
+    pub fn delete(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(db_error("Failed to delete transcript"))?;
+        delete_transcript(&tx, id)?;
+        tx.commit().map_err(db_error("Failed to delete transcript"))
+    }
+
+    /// All history items, newest first
+    pub fn history(&self) -> Result<Vec<HistoryItem>, AppError> {
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare("SELECT id, file_name, file_path, created_at, duration, language FROM transcripts ORDER BY created_at DESC")
+            .map_err(db_error("Failed to read history"))?;
+        let items = statement
+            .query_map([], history_item_from_row)
+            .and_then(|rows| rows.collect())
+            .map_err(db_error("Failed to read history"));
+        items
+    }
+
+    /// Search segment text
+    ///
+    /// Every word of the query must appear in a segment, as a whole word or
+    /// a word prefix, ignoring case and diacritics. Transcripts are ordered
+    /// by their best hit, and hits within a transcript by time.
+    pub fn search(&self, query: &str) -> Result<Vec<SearchResult>, AppError> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare(
+                "SELECT t.id, t.file_name, t.file_path, t.created_at, t.duration, t.language,
+                        s.segment_id, s.start_time, s.end_time, s.text
+                 FROM segments_fts
+                 JOIN segments s ON s.id = segments_fts.rowid
+                 JOIN transcripts t ON t.id = s.transcript_id
+                 WHERE segments_fts MATCH ?1
+                 ORDER BY segments_fts.rank
+                 LIMIT ?2",
+            )
+            .map_err(db_error("Failed to search history"))?;
+        let rows = statement
+            .query_map(params![fts_query, MAX_SEARCH_HITS as i64], |row| {
+                let hit = SegmentHit {
+                    segment_id: row.get(6)?,
+                    start: row.get(7)?,
+                    end: row.get(8)?,
+                    text: row.get(9)?,
+                };
+                Ok((history_item_from_row(row)?, hit))
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(db_error("Failed to search history"))?;
+
+        let mut results: Vec<SearchResult> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for (transcript, hit) in rows {
+            let position = *positions.entry(transcript.id.clone()).or_insert_with(|| {
+                results.push(SearchResult { transcript, hits: Vec::new() });
+                results.len() - 1
+            });
+            results[position].hits.push(hit);
+        }
+        for result in &mut results {
+            result.hits.sort_by(|a, b| a.start.total_cmp(&b.start));
+        }
+        Ok(results)
+    }
+
+    /// Compare the transcript documents with their segment rows
+    ///
+    /// Transcripts whose segments are missing from the search index are
+    /// reported as orphans, segment rows of transcripts that no longer exist
+    /// as dangling, and documents that cannot be parsed as unreadable.
+    pub fn verify(&self) -> Result<StorageReport, AppError> {
+        let conn = self.conn();
+        let check: String = conn
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(db_error("Failed to check history database"))?;
+        if check != "ok" {
+            return Err(AppError::StorageError(format!("History database is corrupt: {}", check)));
+        }
+        conn.execute("INSERT INTO segments_fts (segments_fts) VALUES ('integrity-check')", [])
+            .map_err(db_error("Search index is out of sync; rebuild it"))?;
+
+        check_documents(&conn).map(|(report, _)| report)
+    }
+
+    /// Repair what `verify` reports and recreate the search index
+    ///
+    /// Orphaned transcripts are indexed again from their documents, dangling
+    /// segment rows are deleted, and unreadable documents are moved to
+    /// `quarantine_dir` as `<id>.json`. Returns what was out of sync.
+    pub fn rebuild(&self, quarantine_dir: &Path) -> Result<StorageReport, AppError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(db_error("Failed to rebuild search index"))?;
+        let (report, orphans) = check_documents(&tx)?;
+
+        for transcript in &orphans {
+            insert_transcript(&tx, transcript)?;
+        }
+        tx.execute("DELETE FROM segments WHERE transcript_id NOT IN (SELECT id FROM transcripts)", [])
+            .map_err(db_error("Failed to rebuild search index"))?;
+
+        for id in &report.unreadable {
+            let document: String = tx
+                .query_row("SELECT document FROM transcripts WHERE id = ?1", [id], |row| row.get(0))
+                .map_err(db_error("Failed to quarantine transcript"))?;
+            std::fs::create_dir_all(quarantine_dir)
+                .and_then(|()| std::fs::write(quarantine_dir.join(format!("{}.json", id)), document))
+                .map_err(|e| AppError::StorageError(format!("Failed to quarantine {}: {}", id, e)))?;
+            delete_transcript(&tx, id)?;
+        }
+
+        tx.execute("INSERT INTO segments_fts (segments_fts) VALUES ('rebuild')", [])
+            .map_err(db_error("Failed to rebuild search index"))?;
+        tx.commit().map_err(db_error("Failed to rebuild search index"))?;
+        Ok(report)
+    }
+}
+
+/// Check every transcript document against its segment rows
+///
+/// Returns the report and the parsed orphaned transcripts.
+fn check_documents(conn: &Connection) -> Result<(StorageReport, Vec<StoredTranscript>), AppError> {
+    let mut statement = conn
+        .prepare("SELECT id, document FROM transcripts ORDER BY id")
+        .map_err(db_error("Failed to check history database"))?;
+    let documents = statement
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(db_error("Failed to check history database"))?;
+
+    let mut statement = conn
+        .prepare("SELECT transcript_id, COUNT(*) FROM segments GROUP BY transcript_id ORDER BY transcript_id")
+        .map_err(db_error("Failed to check history database"))?;
+    let mut segment_counts = statement
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, usize>(1)?)))
+        .and_then(|rows| rows.collect::<Result<HashMap<_, _>, _>>())
+        .map_err(db_error("Failed to check history database"))?;
+
+    let mut report = StorageReport::default();
+    let mut orphans = Vec::new();
+    for (id, document) in documents {
+        let indexed = segment_counts.remove(&id).unwrap_or(0);
+        match decode_transcript(&document) {
+            Ok(transcript) if transcript.id == id => {
+                report.transcripts += 1;
+                if transcript.segments.len() != indexed {
+                    report.orphans.push(id);
+                    orphans.push(transcript);
+                }
+            }
+            _ => report.unreadable.push(id),
+        }
+    }
+    report.dangling = segment_counts.into_keys().collect();
+    report.dangling.sort();
+    Ok((report, orphans))
+}
+
+/// Insert a transcript and its segments, replacing any previous version
+fn insert_transcript(tx: &rusqlite::Transaction<'_>, transcript: &StoredTranscript) -> Result<(), AppError> {
+    let document = encode_transcript(transcript)?;
+
+    delete_transcript(tx, &transcript.id)?;
+    tx.execute(
+        "INSERT INTO transcripts (id, file_name, file_path, created_at, duration, language, document)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            transcript.id,
+            transcript.file_name,
+            transcript.file_path,
+            transcript.created_at,
+            transcript.duration,
+            transcript.language,
+            document,
+        ],
+    )
+    .map_err(db_error("Failed to save transcript"))?;
+
+    let mut statement = tx
+        .prepare_cached("INSERT INTO segments (transcript_id, segment_id, start_time, end_time, text) VALUES (?1, ?2, ?3, ?4, ?5)")
+        .map_err(db_error("Failed to save transcript"))?;
+    for segment in &transcript.segments {
+        statement
+            .execute(params![transcript.id, segment.id, segment.start, segment.end, segment.text])
+            .map_err(db_error("Failed to save transcript"))?;
+    }
+    Ok(())
+}
+
+/// Delete a transcript and its segments
+///
+/// Segments are deleted explicitly, so their search index entries are
+/// removed by the delete trigger.
+fn delete_transcript(tx: &rusqlite::Transaction<'_>, id: &str) -> Result<(), AppError> {
+    tx.execute("DELETE FROM segments WHERE transcript_id = ?1", [id])
+        .and_then(|_| tx.execute("DELETE FROM transcripts WHERE id = ?1", [id]))
+        .map(|_| ())
+        .map_err(db_error("Failed to delete transcript"))
+}
+
+/// History item from the first six columns of a row
+fn history_item_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<HistoryItem> {
+    Ok(HistoryItem {
+        id: row.get(0)?,
+        file_name: row.get(1)?,
+        file_path: row.get(2)?,
+        date: row.get(3)?,
+        duration: row.get(4)?,
+        language: row.get(5)?,
+    })
+}
+
+/// FTS5 query matching segments that contain every word of `query`
+///
+/// Each word is quoted, so punctuation and FTS operators in user input are
+/// matched literally instead of failing as query syntax. Returns `None` for
+/// a query without words.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Map a SQLite error to a storage error with context
+fn db_error(context: &'static str) -> impl Fn(rusqlite::Error) -> AppError {
+    move |e| AppError::StorageError(format!("{}: {}", context, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ModelSize, Segment, TranscriptionOptions};
+    use tempfile::TempDir;
+
+    fn create_test_db() -> (HistoryDb, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = HistoryDb::open(&temp_dir.path().join("history.db")).unwrap();
+        (db, temp_dir)
+    }
+
+    fn transcript(id: &str, created_at: &str, texts: &[&str]) -> StoredTranscript {
+        StoredTranscript {
+            id: id.to_string(),
+            file_name: format!("{}.mp3", id),
+            file_path: format!("/recordings/{}.mp3", id),
+            created_at: created_at.to_string(),
+            duration: texts.len() as f64 * 5.0,
+            language: "en".to_string(),
+            model_size: ModelSize::Base,
+            custom_model: None,
+            options: TranscriptionOptions::default(),
+            segments: texts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| Segment {
+                    id: format!("seg_{}", i),
+                    start: i as f64 * 5.0,
+                    end: i as f64 * 5.0 + 4.5,
+                    text: text.to_string(),
+                    words: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_save_load_and_delete() {
+        let (db, _temp) = create_test_db();
+        let older = transcript("older", "2024-01-01T10:00:00Z", &["First meeting"]);
+        let newer = transcript("newer", "2024-02-01T10:00:00Z", &["Second meeting"]);
+        db.save(&older).unwrap();
+        db.save(&newer).unwrap();
+
+        let ids: Vec<String> = db.history().unwrap().into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, vec!["newer", "older"]);
+        let loaded = db.load("older").unwrap();
+        assert_eq!(loaded.file_path, older.file_path);
+        assert_eq!(loaded.segments.len(), 1);
+        assert_eq!(loaded.segments[0].text, "First meeting");
+
+        db.delete("older").unwrap();
+        db.delete("unknown").unwrap();
+        assert!(db.load("older").is_err());
+        assert_eq!(db.history().unwrap().len(), 1);
+        assert!(db.search("first").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_returns_segment_hits_with_timestamps() {
+        let (db, _temp) = create_test_db();
+        db.save(&transcript("a", "2024-01-01T10:00:00Z", &["Welcome to the budget review", "Nothing here", "The budget is approved"])).unwrap();
+        db.save(&transcript("b", "2024-01-02T10:00:00Z", &["Café opening hours"])).unwrap();
+
+        let results = db.search("budget").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].transcript.id, "a");
+        let starts: Vec<f64> = results[0].hits.iter().map(|hit| hit.start).collect();
+        assert_eq!(starts, vec![0.0, 10.0]);
+        assert_eq!(results[0].hits[1].end, 14.5);
+        assert_eq!(results[0].hits[1].text, "The budget is approved");
+
+        // Word prefixes, case and diacritics
+        assert_eq!(db.search("BUDG appro").unwrap()[0].hits.len(), 1);
+        assert_eq!(db.search("cafe").unwrap()[0].transcript.id, "b");
+
+        // Query syntax in user input is matched literally
+        assert!(db.search("\"budget OR (").unwrap().is_empty());
+        assert!(db.search("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_replaces_segments_in_search_index() {
+        let (db, _temp) = create_test_db();
+        db.save(&transcript("a", "2024-01-01T10:00:00Z", &["Draft wording"])).unwrap();
+        db.save(&transcript("a", "2024-01-01T10:00:00Z", &["Final wording"])).unwrap();
+
+        assert!(db.search("draft").unwrap().is_empty());
+        assert_eq!(db.search("final").unwrap().len(), 1);
+        assert_eq!(db.history().unwrap().len(), 1);
+
+        let report = db.verify().unwrap();
+        assert_eq!(report.transcripts, 1);
+        assert!(report.is_consistent());
+    }
+
+    #[test]
+    fn test_verify_and_rebuild() {
+        let (db, temp) = create_test_db();
+        db.save(&transcript("unindexed", "2024-01-01T10:00:00Z", &["Lost budget notes"])).unwrap();
+        db.save(&transcript("kept", "2024-01-02T10:00:00Z", &["Kept notes"])).unwrap();
+        db.save(&transcript("broken", "2024-01-03T10:00:00Z", &["Broken notes"])).unwrap();
+        assert!(db.verify().unwrap().is_consistent());
+
+        // Out of sync: a transcript without segment rows, segment rows
+        // without transcript and a corrupt document
+        {
+            let conn = db.conn();
+            conn.execute_batch(
+                "PRAGMA foreign_keys = OFF;
+                 DELETE FROM segments WHERE transcript_id = 'unindexed';
+                 INSERT INTO segments (transcript_id, segment_id, start_time, end_time, text)
+                     VALUES ('deleted', 'seg_0', 0.0, 1.0, 'Deleted notes');
+                 UPDATE transcripts SET document = '{\"id\": \"bro' WHERE id = 'broken';
+                 PRAGMA foreign_keys = ON;",
+            )
+            .unwrap();
+        }
+
+        let expected = StorageReport {
+            transcripts: 2,
+            orphans: vec!["unindexed".to_string()],
+            dangling: vec!["deleted".to_string()],
+            unreadable: vec!["broken".to_string()],
+        };
+        assert_eq!(db.verify().unwrap(), expected);
+        assert!(db.search("budget").unwrap().is_empty());
+
+        let quarantine = temp.path().join("quarantine");
+        assert_eq!(db.rebuild(&quarantine).unwrap(), expected);
+        assert!(db.verify().unwrap().is_consistent());
+        assert_eq!(db.search("budget").unwrap()[0].transcript.id, "unindexed");
+        let results: Vec<String> = db.search("notes").unwrap().into_iter().map(|r| r.transcript.id).collect();
+        assert_eq!(results.len(), 2);
+        assert!(!results.contains(&"deleted".to_string()));
+        assert_eq!(db.history().unwrap().len(), 2);
+        assert!(quarantine.join("broken.json").exists());
+    }
+
+    #[test]
+    fn test_import_is_recorded() {
+        let (db, temp) = create_test_db();
+        assert!(!db.json_imported().unwrap());
+
+        db.import_json(&[transcript("a", "2024-01-01T10:00:00Z", &["Imported"])]).unwrap();
+        assert!(db.json_imported().unwrap());
+        drop(db);
+
+        let db = HistoryDb::open(&temp.path().join("history.db")).unwrap();
+        assert!(db.json_imported().unwrap());
+        assert_eq!(db.search("imported").unwrap().len(), 1);
+    }
+}
@@ -1,10 +1,18 @@
+pub mod backend;
+pub mod diagnostics;
 pub mod export;
 pub mod ffmpeg;
 pub mod file_handler;
+pub mod history_db;
+#[cfg(any(test, feature = "mock-backend"))]
+pub mod mock_backend;
+pub mod model_manager;
 pub mod models;
 pub mod sidecar;
 pub mod storage;
+pub mod throughput;
 pub mod tray;
+pub mod worker;
 
 use std::sync::Arc;
 use tauri::{Manager, Emitter};
@@ -16,7 +24,9 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-/// Start a transcription job
+/// Start a transcription job, optionally from a position in seconds
+/// 
+/// Jobs without options use the defaults from settings.
 /// 
 /// Requirements: 2.3
 #[tauri::command]
@@ -25,25 +35,209 @@ async fn start_transcription(
     state: tauri::State<'_, Arc<SidecarManager>>,
     file_path: String,
     model_size: models::ModelSize,
+    start_offset: Option<f64>,
+    options: Option<models::TranscriptionOptions>,
 ) -> Result<String, String> {
     state
-        .start_transcription(app, file_path, model_size)
+        .start_transcription(app, file_path, model_size, start_offset.unwrap_or(0.0), options)
         .await
         .map_err(|e| e.to_string())
 }
 
-/// Cancel an active transcription job
+/// Queue several files for transcription, in order
+#[tauri::command]
+async fn enqueue_transcriptions(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    file_paths: Vec<String>,
+    model_size: models::ModelSize,
+    options: Option<models::TranscriptionOptions>,
+) -> Result<Vec<String>, String> {
+    state
+        .enqueue_transcriptions(app, file_paths, model_size, options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Cancel a queued or active transcription job
 /// 
 /// Requirements: 2.8
 #[tauri::command]
 async fn cancel_transcription(
+    app: tauri::AppHandle,
     state: tauri::State<'_, Arc<SidecarManager>>,
     job_id: String,
 ) -> Result<(), String> {
     state
         .cancel_transcription(&job_id)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Pause a running transcription job
+#[tauri::command]
+async fn pause_transcription(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+) -> Result<(), String> {
+    state
+        .pause_transcription(&job_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Resume a paused transcription job
+#[tauri::command]
+async fn resume_transcription(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+) -> Result<(), String> {
+    state
+        .resume_transcription(&job_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Queue an interrupted or failed transcription job again
+#[tauri::command]
+async fn retry_transcription(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+) -> Result<(), String> {
+    state
+        .retry_transcription(&job_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Change the priority of a transcription job
+#[tauri::command]
+async fn set_job_priority(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+    priority: models::JobPriority,
+) -> Result<(), String> {
+    state
+        .set_job_priority(&job_id, priority)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Move a queued transcription job to a position in the queue
+#[tauri::command]
+async fn move_job(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+    position: usize,
+) -> Result<(), String> {
+    state
+        .move_job(&job_id, position)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Run a queued transcription job next
+#[tauri::command]
+async fn bump_job(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+) -> Result<(), String> {
+    state
+        .bump_job(&job_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Get the current backend queue state
+#[tauri::command]
+async fn get_queue_state(
+    state: tauri::State<'_, Arc<SidecarManager>>,
+) -> Result<sidecar::QueueChangedPayload, String> {
+    Ok(state.queue_snapshot().await)
+}
+
+/// List all tracked transcription jobs with their current status
+#[tauri::command]
+async fn list_jobs(
+    state: tauri::State<'_, Arc<SidecarManager>>,
+) -> Result<Vec<sidecar::JobInfo>, String> {
+    Ok(state.list_jobs().await)
+}
+
+/// Get the current status of a single transcription job
+#[tauri::command]
+async fn get_job(
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    job_id: String,
+) -> Result<sidecar::JobInfo, String> {
+    state.get_job(&job_id).await.map_err(|e| e.to_string())
+}
+
+/// Change how many transcription jobs may run at once, and keep it in settings
+#[tauri::command]
+async fn set_max_concurrent_jobs(
+    app: tauri::AppHandle,
+    storage: tauri::State<'_, Arc<storage::StorageManager>>,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    max_concurrent: usize,
+) -> Result<(), String> {
+    let mut settings = storage.load_settings().map_err(|e| e.to_string())?;
+    settings.max_concurrent_jobs = max_concurrent.max(1);
+    storage.save_settings(&settings).map_err(|e| e.to_string())?;
+
+    state.set_max_concurrent_jobs(max_concurrent);
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Save application settings and apply them to the running job queue
+///
+/// Requirements: 9.5
+#[tauri::command]
+async fn save_settings(
+    app: tauri::AppHandle,
+    storage: tauri::State<'_, Arc<storage::StorageManager>>,
+    state: tauri::State<'_, Arc<SidecarManager>>,
+    settings: models::Settings,
+) -> Result<(), String> {
+    let previous = storage.load_settings().ok();
+    storage.save_settings(&settings).map_err(|e| e.to_string())?;
+
+    // Only a different backend replaces the warm workers
+    let backend_changed = previous.map(|previous| previous.transcription_backend).as_ref()
+        != Some(&settings.transcription_backend);
+    let backend = backend_changed.then(|| backend::from_settings(app.clone(), &settings.transcription_backend));
+    state.apply_settings(&settings, backend).await;
+    state.emit_queue_changed(&app).await;
+    Ok(())
+}
+
+/// Get the average transcription speed of each model, for estimating jobs before they start
+#[tauri::command]
+async fn get_throughput_stats(
+    state: tauri::State<'_, Arc<SidecarManager>>,
+) -> Result<throughput::ThroughputStats, String> {
+    Ok(state.throughput_stats().await)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -54,8 +248,45 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .setup(|app| {
+            // Share one storage manager, so concurrent index updates are serialized.
+            // Storage commands and the sidecar depend on it, so startup fails without it.
+            let storage = Arc::new(storage::get_storage_manager()?);
+            app.manage(Arc::clone(&storage));
+
+            // Tell the user why saving and deleting transcripts will fail
+            if let Some(reason) = storage.history_read_only() {
+                use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+                app.dialog()
+                    .message(format!(
+                        "The transcript history is read-only because {}. Recent transcripts may be missing \
+                         and deleted ones may reappear until the database can be opened again.",
+                        reason
+                    ))
+                    .kind(MessageDialogKind::Warning)
+                    .title("ScriptGrab")
+                    .show(|_| {});
+            }
+
             // Initialize the sidecar manager as managed state
-            let sidecar_manager = Arc::new(SidecarManager::new());
+            let settings = storage.load_settings().unwrap_or_else(|e| {
+                eprintln!("Failed to load settings: {}", e);
+                Default::default()
+            });
+            let mut sidecar_manager = SidecarManager::with_max_concurrent(settings.max_concurrent_jobs)
+                .with_watchdog_timeout(settings.watchdog_timeout_secs)
+                .with_backend(backend::from_settings(app.handle().clone(), &settings.transcription_backend))
+                .with_storage(storage);
+            if let Ok(models) = model_manager::get_model_manager() {
+                sidecar_manager = sidecar_manager.with_model_manager(models);
+            }
+
+            // Resume the batch left over from the previous session
+            if let Err(e) = tauri::async_runtime::block_on(sidecar_manager.restore_queue()) {
+                eprintln!("Failed to restore job queue: {}", e);
+            }
+
+            let sidecar_manager = Arc::new(sidecar_manager);
+            sidecar_manager.start_dispatcher(app.handle().clone());
             app.manage(sidecar_manager);
 
             // Setup system tray
@@ -77,7 +308,7 @@ pub fn run() {
                         let app_clone = app_handle.clone();
                         tauri::async_runtime::spawn(async move {
                             // Check if minimize to tray is enabled
-                            let minimize_to_tray = tray::is_minimize_to_tray_enabled();
+                            let minimize_to_tray = tray::is_minimize_to_tray_enabled(&app_clone);
                             
                             // Check if there are active transcription jobs
                             let has_active_jobs = tray::has_active_transcription(&app_clone).await;
@@ -107,15 +338,34 @@ pub fn run() {
             file_handler::get_file_metadata,
             file_handler::validate_file,
             start_transcription,
+            enqueue_transcriptions,
             cancel_transcription,
+            pause_transcription,
+            resume_transcription,
+            retry_transcription,
+            set_job_priority,
+            move_job,
+            bump_job,
+            get_queue_state,
+            list_jobs,
+            get_job,
+            set_max_concurrent_jobs,
+            get_throughput_stats,
+            model_manager::list_models,
+            model_manager::verify_model,
+            model_manager::import_model,
+            model_manager::delete_model,
             export::export_transcript_to_file,
             export::export_with_dialog,
             storage::get_history,
             storage::delete_history_item,
             storage::load_history_item,
+            storage::search_history,
+            storage::verify_storage,
+            storage::rebuild_index,
             storage::save_transcript,
             storage::get_settings,
-            storage::save_settings,
+            save_settings,
             tray::has_active_jobs,
             tray::confirm_quit,
             tray::minimize_to_tray,
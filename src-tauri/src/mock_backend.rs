@@ -0,0 +1,267 @@
+//! Mock Transcription Backend Module
+//!
+//! A backend whose workers replay a scripted sequence of sidecar messages
+//! instead of running an engine. It drives the whole job lifecycle — events,
+//! history saving, cancellation, crashes and hangs — without Python, models
+//! or media files, so it can run on a headless CI machine.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tokio::sync::mpsc;
+
+use crate::backend::{parse_output, BackendEvent, BackendProcess, SidecarOutput, TranscriptionBackend, EVENT_BUFFER};
+use crate::diagnostics::ExitStatus;
+use crate::models::{AppError, EngineInfo, EngineRequest, ModelSize, Segment, SidecarMessage};
+use crate::sidecar::SIDECAR_PROTOCOL_VERSION;
+
+/// One step of a mock worker's script
+#[derive(Debug, Clone)]
+pub enum MockStep {
+    /// Send a protocol message
+    Message(SidecarMessage),
+    /// Write a raw line to stdout, such as malformed JSON or library noise
+    Stdout(String),
+    /// Write a line to stderr
+    Stderr(String),
+    /// Wait before the next step
+    Delay(Duration),
+    /// Exit with the given status, as a crashed engine would
+    Crash(ExitStatus),
+    /// Stop responding until the worker is killed
+    Hang,
+}
+
+impl MockStep {
+    /// Steps of a successful transcription of the given segments
+    pub fn transcript(language: &str, duration: f64, segments: Vec<Segment>) -> Vec<MockStep> {
+        let mut steps = vec![MockStep::Message(SidecarMessage::Progress {
+            percent: 20,
+            status: "Model loaded, starting transcription...".to_string(),
+        })];
+        steps.extend(segments.into_iter().map(|data| MockStep::Message(SidecarMessage::Segment { data })));
+        steps.push(MockStep::Message(SidecarMessage::Complete {
+            language: language.to_string(),
+            duration,
+        }));
+        steps
+    }
+}
+
+/// Backend whose workers replay a script for every job they receive
+///
+/// Cloning yields a handle to the same recorded requests and spawn count.
+#[derive(Debug, Clone)]
+pub struct MockBackend {
+    hello: Option<EngineInfo>,
+    script: Arc<Vec<MockStep>>,
+    requests: Arc<Mutex<Vec<EngineRequest>>>,
+    spawned: Arc<AtomicUsize>,
+}
+
+impl MockBackend {
+    /// Create a backend whose workers greet with a compatible handshake and
+    /// then play `script` for each job
+    pub fn new(script: Vec<MockStep>) -> Self {
+        Self {
+            hello: Some(Self::engine_info()),
+            script: Arc::new(script),
+            requests: Arc::new(Mutex::new(Vec::new())),
+            spawned: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Send the given handshake instead of the default one, or none at all
+    pub fn with_hello(mut self, hello: Option<EngineInfo>) -> Self {
+        self.hello = hello;
+        self
+    }
+
+    /// Handshake of an engine that supports every model on the CPU
+    pub fn engine_info() -> EngineInfo {
+        EngineInfo {
+            protocol_version: SIDECAR_PROTOCOL_VERSION,
+            engine_version: "mock".to_string(),
+            models: ModelSize::BUILT_IN.iter().filter_map(|m| m.engine_name()).map(str::to_string).collect(),
+            devices: vec!["cpu".to_string()],
+        }
+    }
+
+    /// Get every job request sent to a worker so far, in order
+    pub fn requests(&self) -> Vec<EngineRequest> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Get the number of workers started so far
+    pub fn spawn_count(&self) -> usize {
+        self.spawned.load(Ordering::SeqCst)
+    }
+}
+
+impl TranscriptionBackend for MockBackend {
+    fn name(&self) -> String {
+        "mock".to_string()
+    }
+
+    fn spawn(&self) -> Result<(Box<dyn BackendProcess>, mpsc::Receiver<BackendEvent>), AppError> {
+        self.spawned.fetch_add(1, Ordering::SeqCst);
+
+        let (events, rx) = mpsc::channel(EVENT_BUFFER);
+        let (jobs, mut pending) = mpsc::unbounded_channel::<EngineRequest>();
+        let hello = self.hello.clone();
+        let script = self.script.clone();
+        let tx = events.clone();
+
+        let task = tauri::async_runtime::spawn(async move {
+            if let Some(hello) = hello {
+                let _ = tx.send(message(SidecarMessage::Hello(hello))).await;
+            }
+
+            while pending.recv().await.is_some() {
+                for step in script.iter() {
+                    let event = match step {
+                        MockStep::Message(msg) => message(msg.clone()),
+                        MockStep::Stdout(line) => match parse_output(line.clone()) {
+                            Some(event) => event,
+                            None => continue,
+                        },
+                        MockStep::Stderr(line) => BackendEvent::Stderr(line.clone()),
+                        MockStep::Delay(delay) => {
+                            tokio::time::sleep(*delay).await;
+                            continue;
+                        }
+                        MockStep::Crash(exit) => {
+                            let _ = tx.send(BackendEvent::Terminated(*exit)).await;
+                            return;
+                        }
+                        MockStep::Hang => std::future::pending().await,
+                    };
+                    if tx.send(event).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            // Stdin closed, like a real worker at shutdown
+            let _ = tx.send(BackendEvent::Terminated(ExitStatus { code: Some(0), signal: None })).await;
+        });
+
+        let process = MockProcess {
+            jobs,
+            events,
+            task,
+            requests: self.requests.clone(),
+        };
+        Ok((Box::new(process), rx))
+    }
+}
+
+fn message(message: SidecarMessage) -> BackendEvent {
+    BackendEvent::Output(SidecarOutput::Message(message))
+}
+
+/// A mock worker, running its script on the async runtime
+#[derive(Debug)]
+struct MockProcess {
+    jobs: mpsc::UnboundedSender<EngineRequest>,
+    events: mpsc::Sender<BackendEvent>,
+    task: JoinHandle<()>,
+    requests: Arc<Mutex<Vec<EngineRequest>>>,
+}
+
+impl BackendProcess for MockProcess {
+    fn pid(&self) -> Option<u32> {
+        None
+    }
+
+    fn send(&mut self, request: &EngineRequest) -> Result<(), AppError> {
+        self.jobs
+            .send(request.clone())
+            .map_err(|_| AppError::SidecarError("Failed to send job to worker: worker exited".to_string()))?;
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner).push(request.clone());
+        Ok(())
+    }
+
+    fn kill(self: Box<Self>) -> Result<(), AppError> {
+        self.task.abort();
+        let events = self.events;
+        tauri::async_runtime::spawn(async move {
+            let _ = events.send(BackendEvent::Terminated(ExitStatus { code: None, signal: Some(9) })).await;
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TranscriptionTask;
+
+    fn request(job_id: &str) -> EngineRequest {
+        EngineRequest::Transcribe {
+            job_id: job_id.to_string(),
+            audio_path: "/in/a.mp3".to_string(),
+            model: "base".to_string(),
+            start_offset: 0.0,
+            language: None,
+            task: TranscriptionTask::Transcribe,
+            initial_prompt: None,
+            temperature: 0.0,
+            device: None,
+            threads: None,
+        }
+    }
+
+    async fn next(rx: &mut mpsc::Receiver<BackendEvent>) -> BackendEvent {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("timed out waiting for a mock event")
+            .expect("mock worker closed its events")
+    }
+
+    #[test]
+    fn test_mock_worker_replays_script_per_job() {
+        tauri::async_runtime::block_on(async {
+            let backend = MockBackend::new(vec![
+                MockStep::Stderr("loading".to_string()),
+                MockStep::Delay(Duration::from_millis(10)),
+                MockStep::Stdout("not json".to_string()),
+                MockStep::Message(SidecarMessage::Complete { language: "en".to_string(), duration: 1.0 }),
+            ]);
+            let (mut process, mut rx) = backend.spawn().unwrap();
+
+            assert!(matches!(next(&mut rx).await, BackendEvent::Output(SidecarOutput::Message(SidecarMessage::Hello(_)))));
+
+            for job_id in ["job-1", "job-2"] {
+                process.send(&request(job_id)).unwrap();
+                assert!(matches!(next(&mut rx).await, BackendEvent::Stderr(line) if line == "loading"));
+                assert!(matches!(next(&mut rx).await, BackendEvent::Output(SidecarOutput::Invalid { .. })));
+                assert!(matches!(
+                    next(&mut rx).await,
+                    BackendEvent::Output(SidecarOutput::Message(SidecarMessage::Complete { .. }))
+                ));
+            }
+
+            assert_eq!(backend.requests(), vec![request("job-1"), request("job-2")]);
+            assert_eq!(backend.spawn_count(), 1);
+        });
+    }
+
+    #[test]
+    fn test_mock_worker_crashes_and_hangs() {
+        tauri::async_runtime::block_on(async {
+            let crash = ExitStatus { code: Some(137), signal: None };
+            let backend = MockBackend::new(vec![MockStep::Crash(crash)]).with_hello(None);
+            let (mut process, mut rx) = backend.spawn().unwrap();
+            process.send(&request("job-1")).unwrap();
+            assert!(matches!(next(&mut rx).await, BackendEvent::Terminated(exit) if exit == crash));
+
+            let backend = MockBackend::new(vec![MockStep::Hang]).with_hello(None);
+            let (mut process, mut rx) = backend.spawn().unwrap();
+            process.send(&request("job-1")).unwrap();
+            process.kill().unwrap();
+            assert!(matches!(next(&mut rx).await, BackendEvent::Terminated(ExitStatus { signal: Some(9), .. })));
+        });
+    }
+}
@@ -0,0 +1,706 @@
+//! Model Manager Module
+//!
+//! Whisper models live in the engine's download cache, `~/.cache/whisper`
+//! (or `$XDG_CACHE_HOME/whisper`), as one checkpoint file per model. This
+//! module reports which models are there, checks them against the SHA-256
+//! checksums published with the official checkpoints, and deletes them to
+//! free disk space.
+//! Machines without internet access import checkpoints from a file, a
+//! directory or a zip archive instead of downloading them. Official
+//! checkpoints are recognised by their checksum; anything else is kept as a
+//! custom model.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::models::{AppError, ModelInfo, ModelIntegrity, ModelSize, ModelVerification};
+
+/// An official Whisper checkpoint
+#[derive(Debug, Clone, Copy)]
+pub struct KnownModel {
+    pub size: ModelSize,
+    /// File name in the model cache
+    pub file_name: &'static str,
+    /// SHA-256 checksum of the checkpoint, lowercase hex
+    pub sha256: &'static str,
+    /// Approximate download size, for display
+    pub download_size: &'static str,
+}
+
+/// Checkpoints of every built-in model size
+///
+/// `large` is an alias of `large-v3`, so both share a file.
+pub const KNOWN_MODELS: [KnownModel; 8] = [
+    KnownModel {
+        size: ModelSize::Tiny,
+        file_name: "tiny.pt",
+        sha256: "65147644a518d12f04e32d6f3b26facc3f8dd46e5390956a9424a650c0ce22b9",
+        download_size: "72 MB",
+    },
+    KnownModel {
+        size: ModelSize::Base,
+        file_name: "base.pt",
+        sha256: "ed3a0b6b1c0edf879ad9b11b1af5a0e6ab5db9205f891f668f8b0e6c6326e34e",
+        download_size: "139 MB",
+    },
+    KnownModel {
+        size: ModelSize::Small,
+        file_name: "small.pt",
+        sha256: "9ecf779972d90ba49c06d968637d720dd632c55bbf19d441fb42bf17a411e794",
+        download_size: "461 MB",
+    },
+    KnownModel {
+        size: ModelSize::Medium,
+        file_name: "medium.pt",
+        sha256: "345ae4da62f9b3d59415adc60127b97c714f32e89e936602e85993674d08dcb1",
+        download_size: "1.42 GB",
+    },
+    KnownModel {
+        size: ModelSize::Large,
+        file_name: "large-v3.pt",
+        sha256: "e5b1a55b89c1367dacf97e3e19bfd829a01529dbfdeefa8caeb59b3f1b81dadb",
+        download_size: "2.88 GB",
+    },
+    KnownModel {
+        size: ModelSize::LargeV2,
+        file_name: "large-v2.pt",
+        sha256: "81f7c96c852ee8fc832187b0132e569d6c3065a3252ed18e56effd0b6a73e524",
+        download_size: "2.87 GB",
+    },
+    KnownModel {
+        size: ModelSize::LargeV3,
+        file_name: "large-v3.pt",
+        sha256: "e5b1a55b89c1367dacf97e3e19bfd829a01529dbfdeefa8caeb59b3f1b81dadb",
+        download_size: "2.88 GB",
+    },
+    KnownModel {
+        size: ModelSize::Turbo,
+        file_name: "large-v3-turbo.pt",
+        sha256: "aff26ae408abcba5fbf8813c21e62b0941638c5f6eebfb145be0c9839262a19a",
+        download_size: "1.51 GB",
+    },
+];
+
+/// Get the official checkpoint of a model size; `None` for a custom model
+pub fn known_model(size: ModelSize) -> Option<&'static KnownModel> {
+    KNOWN_MODELS.iter().find(|model| model.size == size)
+}
+
+/// Find the official checkpoint with a checksum
+///
+/// Searched from the back so that the `large-v3` checkpoint is reported as
+/// `large-v3` rather than its `large` alias.
+fn known_model_by_sha256(sha256: &str) -> Option<&'static KnownModel> {
+    KNOWN_MODELS.iter().rev().find(|model| model.sha256 == sha256)
+}
+
+/// Subdirectory of the model cache that holds imported custom checkpoints
+const CUSTOM_MODEL_DIR: &str = "custom";
+
+/// File extension of Whisper checkpoints
+const CHECKPOINT_EXTENSION: &str = "pt";
+
+/// Leading bytes of a PyTorch checkpoint: a zip container (PyTorch 1.6+) or a legacy pickle
+const CHECKPOINT_MAGIC: [&[u8]; 2] = [b"PK\x03\x04", b"\x80"];
+
+/// Manages the checkpoint files in the model cache
+pub struct ModelManager {
+    model_dir: PathBuf,
+}
+
+impl ModelManager {
+    /// Create a model manager for the given cache directory
+    pub fn new(model_dir: PathBuf) -> Self {
+        Self { model_dir }
+    }
+
+    /// Get the cache directory whisper-engine downloads models to
+    pub fn default_model_dir() -> Result<PathBuf, AppError> {
+        if let Some(cache_home) = std::env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+            return Ok(PathBuf::from(cache_home).join("whisper"));
+        }
+
+        let base_dirs = directories::BaseDirs::new()
+            .ok_or_else(|| AppError::StorageError("Cannot determine base directories".to_string()))?;
+        Ok(base_dirs.home_dir().join(".cache").join("whisper"))
+    }
+
+    /// Get the path a model's checkpoint has in the cache
+    fn model_path(&self, model: &KnownModel) -> PathBuf {
+        self.model_dir.join(model.file_name)
+    }
+
+    fn known(size: ModelSize) -> Result<&'static KnownModel, AppError> {
+        known_model(size).ok_or_else(|| {
+            AppError::InvalidInput("Custom models are not managed by the model cache".to_string())
+        })
+    }
+
+    /// Get the directory imported custom checkpoints are kept in
+    fn custom_model_dir(&self) -> PathBuf {
+        self.model_dir.join(CUSTOM_MODEL_DIR)
+    }
+
+    /// List every built-in model and whether it is in the cache, followed by
+    /// imported custom checkpoints
+    pub fn list_models(&self) -> Vec<ModelInfo> {
+        let mut models: Vec<ModelInfo> = KNOWN_MODELS.iter().map(|model| self.info(model)).collect();
+        models.extend(self.custom_models());
+        models
+    }
+
+    /// Whether a built-in model's checkpoint is in the cache
+    pub fn is_installed(&self, size: ModelSize) -> bool {
+        known_model(size).is_some_and(|model| self.model_path(model).is_file())
+    }
+
+    /// Find an imported custom checkpoint by its file name, with or without
+    /// the `.pt` extension
+    pub fn custom_model_path(&self, name: &str) -> Option<PathBuf> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return None;
+        }
+
+        let dir = self.custom_model_dir();
+        [name.to_string(), format!("{}.{}", name, CHECKPOINT_EXTENSION)]
+            .into_iter()
+            .map(|file_name| dir.join(file_name))
+            .find(|path| is_checkpoint_name(path) && path.is_file())
+    }
+
+    /// Get the cache status of a single model
+    pub fn model_info(&self, size: ModelSize) -> Result<ModelInfo, AppError> {
+        Ok(self.info(Self::known(size)?))
+    }
+
+    fn info(&self, model: &KnownModel) -> ModelInfo {
+        let path = self.model_path(model);
+        let metadata = fs::metadata(&path).ok().filter(|m| m.is_file());
+
+        ModelInfo {
+            name: model.size,
+            size: model.download_size.to_string(),
+            downloaded: metadata.is_some(),
+            size_on_disk: metadata.map(|m| m.len()).unwrap_or(0),
+            path: Some(path.to_string_lossy().into_owned()),
+        }
+    }
+
+    /// List the imported custom checkpoints, by file name
+    fn custom_models(&self) -> Vec<ModelInfo> {
+        let Ok(entries) = fs::read_dir(self.custom_model_dir()) else {
+            return Vec::new();
+        };
+
+        let mut models: Vec<ModelInfo> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let metadata = fs::metadata(&path).ok().filter(|m| m.is_file())?;
+                is_checkpoint_name(&path).then(|| custom_model_info(&path, metadata.len()))
+            })
+            .collect();
+        models.sort_by(|a, b| a.path.cmp(&b.path));
+        models
+    }
+
+    /// Check a model's checkpoint against its published checksum
+    ///
+    /// Reads the whole file, which takes a while for the large models.
+    pub fn verify_model(&self, size: ModelSize) -> Result<ModelVerification, AppError> {
+        let model = Self::known(size)?;
+        let path = self.model_path(model);
+
+        let (integrity, actual_sha256) = if path.is_file() {
+            let actual = sha256_file(&path)
+                .map_err(|e| AppError::StorageError(format!("Failed to read model {}: {}", model.file_name, e)))?;
+            let integrity = if actual == model.sha256 {
+                ModelIntegrity::Valid
+            } else {
+                ModelIntegrity::Corrupt
+            };
+            (integrity, Some(actual))
+        } else {
+            (ModelIntegrity::Missing, None)
+        };
+
+        Ok(ModelVerification {
+            name: size,
+            integrity,
+            expected_sha256: model.sha256.to_string(),
+            actual_sha256,
+        })
+    }
+
+    /// Delete a model from the cache
+    ///
+    /// `large` and `large-v3` share a checkpoint, so deleting one deletes both.
+    pub fn delete_model(&self, size: ModelSize) -> Result<(), AppError> {
+        let model = Self::known(size)?;
+        let path = self.model_path(model);
+        if !path.is_file() {
+            return Err(AppError::ModelNotFound(model.file_name.to_string()));
+        }
+
+        fs::remove_file(&path)
+            .map_err(|e| AppError::StorageError(format!("Failed to delete model {}: {}", model.file_name, e)))
+    }
+
+    // ============================================
+    // Offline Import
+    // ============================================
+
+    /// Import checkpoints from a `.pt` file, a directory of them or a zip archive
+    ///
+    /// Every checkpoint is copied into the cache. One whose checksum matches
+    /// an official checkpoint is installed under that model's name; one named
+    /// like an official checkpoint but with a different checksum is rejected
+    /// as corrupt; any other checkpoint is kept as a custom model. A custom
+    /// checkpoint is rejected if a different one was already imported under
+    /// its name. Returns the imported models.
+    pub fn import_model(&self, source: &Path) -> Result<Vec<ModelInfo>, AppError> {
+        if !source.exists() {
+            return Err(AppError::FileNotFound(source.to_string_lossy().into_owned()));
+        }
+
+        let imported = if source.is_dir() {
+            self.import_directory(source)?
+        } else if has_extension(source, "zip") {
+            self.import_archive(source)?
+        } else {
+            let file = fs::File::open(source)
+                .map_err(|e| AppError::StorageError(format!("Failed to open {}: {}", source.display(), e)))?;
+            vec![self.import_checkpoint(file, &file_name(source))?]
+        };
+
+        if imported.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "No Whisper checkpoint (.{}) found in {}",
+                CHECKPOINT_EXTENSION,
+                source.display()
+            )));
+        }
+        Ok(imported)
+    }
+
+    fn import_directory(&self, dir: &Path) -> Result<Vec<ModelInfo>, AppError> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| AppError::StorageError(format!("Failed to read {}: {}", dir.display(), e)))?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && is_checkpoint_name(path))
+            .collect();
+        paths.sort();
+
+        paths
+            .iter()
+            .map(|path| {
+                let file = fs::File::open(path)
+                    .map_err(|e| AppError::StorageError(format!("Failed to open {}: {}", path.display(), e)))?;
+                self.import_checkpoint(file, &file_name(path))
+            })
+            .collect()
+    }
+
+    fn import_archive(&self, archive_path: &Path) -> Result<Vec<ModelInfo>, AppError> {
+        let file = fs::File::open(archive_path)
+            .map_err(|e| AppError::StorageError(format!("Failed to open {}: {}", archive_path.display(), e)))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| AppError::InvalidInput(format!("Not a valid zip archive: {}", e)))?;
+
+        let mut imported = Vec::new();
+        for index in 0..archive.len() {
+            let entry = archive
+                .by_index(index)
+                .map_err(|e| AppError::InvalidInput(format!("Failed to read archive entry: {}", e)))?;
+            // Entries are imported by file name only, so paths in the archive cannot escape the cache
+            let Some(name) = entry.enclosed_name().map(|path| file_name(&path)) else {
+                continue;
+            };
+            if !entry.is_file() || !is_checkpoint_name(Path::new(&name)) {
+                continue;
+            }
+            imported.push(self.import_checkpoint(entry, &name)?);
+        }
+        Ok(imported)
+    }
+
+    /// Copy one checkpoint into the cache, hashing it on the way
+    ///
+    /// The data is staged next to its destination and only renamed into place
+    /// once it has been checked, so a failed import leaves nothing behind.
+    fn import_checkpoint(&self, mut reader: impl Read, name: &str) -> Result<ModelInfo, AppError> {
+        fs::create_dir_all(self.custom_model_dir())
+            .map_err(|e| AppError::StorageError(format!("Failed to create model directory: {}", e)))?;
+
+        let staged = self.model_dir.join(format!(".import-{}.tmp", Uuid::new_v4()));
+        let result = copy_with_sha256(&mut reader, &staged)
+            .map_err(|e| AppError::StorageError(format!("Failed to copy {}: {}", name, e)))
+            .and_then(|sha256| self.install_checkpoint(&staged, name, &sha256));
+        if result.is_err() {
+            let _ = fs::remove_file(&staged);
+        }
+        result
+    }
+
+    fn install_checkpoint(&self, staged: &Path, name: &str, sha256: &str) -> Result<ModelInfo, AppError> {
+        if !has_checkpoint_magic(staged) {
+            return Err(AppError::InvalidInput(format!("{} is not a Whisper checkpoint", name)));
+        }
+
+        let (destination, known) = match known_model_by_sha256(sha256) {
+            Some(model) => (self.model_path(model), Some(model)),
+            None if KNOWN_MODELS.iter().any(|model| model.file_name == name) => {
+                return Err(AppError::InvalidInput(format!(
+                    "{} does not match the published checksum; the file is corrupt or incomplete",
+                    name
+                )));
+            }
+            None => {
+                let destination = self.custom_model_dir().join(name);
+                // Re-importing the same checkpoint is harmless; a different one would replace it
+                if destination.exists() && sha256_file(&destination).ok().as_deref() != Some(sha256) {
+                    return Err(AppError::InvalidInput(format!(
+                        "A different custom model named {} is already imported; rename the file or delete the existing model first",
+                        name
+                    )));
+                }
+                (destination, None)
+            }
+        };
+
+        fs::rename(staged, &destination)
+            .map_err(|e| AppError::StorageError(format!("Failed to install {}: {}", name, e)))?;
+
+        Ok(match known {
+            Some(model) => self.info(model),
+            None => {
+                let size = fs::metadata(&destination).map(|m| m.len()).unwrap_or(0);
+                custom_model_info(&destination, size)
+            }
+        })
+    }
+}
+
+/// Describe an imported custom checkpoint
+fn custom_model_info(path: &Path, size_on_disk: u64) -> ModelInfo {
+    ModelInfo {
+        name: ModelSize::Custom,
+        size: format_size(size_on_disk),
+        downloaded: true,
+        size_on_disk,
+        path: Some(path.to_string_lossy().into_owned()),
+    }
+}
+
+/// Format a byte count for display, e.g. "461 MB"
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.2} GB", bytes / GB)
+    } else {
+        format!("{:.0} MB", bytes / MB)
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(extension))
+}
+
+/// Whether a file is named like a Whisper checkpoint
+fn is_checkpoint_name(path: &Path) -> bool {
+    has_extension(path, CHECKPOINT_EXTENSION) && !file_name(path).starts_with('.')
+}
+
+/// Whether a file starts like a PyTorch checkpoint
+fn has_checkpoint_magic(path: &Path) -> bool {
+    let mut header = [0u8; 4];
+    let Ok(read) = fs::File::open(path).and_then(|mut file| file.read(&mut header)) else {
+        return false;
+    };
+    CHECKPOINT_MAGIC.iter().any(|magic| header[..read].starts_with(magic))
+}
+
+/// Copy everything from a reader into a new file and return its SHA-256 checksum
+fn copy_with_sha256(reader: &mut impl Read, destination: &Path) -> io::Result<String> {
+    let mut file = fs::File::create(destination)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])?;
+    }
+    file.sync_all()?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Compute the SHA-256 checksum of a file as lowercase hex
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Get the model manager for the engine's model cache
+pub fn get_model_manager() -> Result<ModelManager, String> {
+    let model_dir = ModelManager::default_model_dir().map_err(|e| e.to_string())?;
+    Ok(ModelManager::new(model_dir))
+}
+
+/// List the built-in models and their cache status
+#[tauri::command]
+pub async fn list_models() -> Result<Vec<ModelInfo>, String> {
+    Ok(get_model_manager()?.list_models())
+}
+
+/// Check a downloaded model against its published checksum
+#[tauri::command]
+pub async fn verify_model(model_size: ModelSize) -> Result<ModelVerification, String> {
+    let manager = get_model_manager()?;
+    tauri::async_runtime::spawn_blocking(move || manager.verify_model(model_size))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Import models from a checkpoint file, a directory or a zip archive on disk
+#[tauri::command]
+pub async fn import_model(source_path: String) -> Result<Vec<ModelInfo>, String> {
+    let manager = get_model_manager()?;
+    tauri::async_runtime::spawn_blocking(move || manager.import_model(Path::new(&source_path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a downloaded model to free disk space
+#[tauri::command]
+pub async fn delete_model(model_size: ModelSize) -> Result<(), String> {
+    let manager = get_model_manager()?;
+    manager.delete_model(model_size).map_err(|e| e.to_string())
+}
+
+// ============================================
+// Tests
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_manager() -> (ModelManager, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ModelManager::new(temp_dir.path().to_path_buf());
+        (manager, temp_dir)
+    }
+
+    #[test]
+    fn test_every_built_in_size_is_known() {
+        for size in ModelSize::BUILT_IN {
+            let model = known_model(size).unwrap();
+            assert_eq!(model.sha256.len(), 64);
+            assert!(model.file_name.ends_with(".pt"));
+        }
+        assert!(known_model(ModelSize::Custom).is_none());
+    }
+
+    #[test]
+    fn test_list_models_reports_cache_status() {
+        let (manager, temp_dir) = create_test_manager();
+        fs::write(temp_dir.path().join("small.pt"), b"checkpoint").unwrap();
+
+        let models = manager.list_models();
+        assert_eq!(models.len(), ModelSize::BUILT_IN.len());
+
+        let small = models.iter().find(|m| m.name == ModelSize::Small).unwrap();
+        assert!(small.downloaded);
+        assert_eq!(small.size_on_disk, 10);
+        assert_eq!(small.size, "461 MB");
+
+        let base = manager.model_info(ModelSize::Base).unwrap();
+        assert!(!base.downloaded);
+        assert_eq!(base.size_on_disk, 0);
+
+        assert!(matches!(manager.model_info(ModelSize::Custom), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_verify_model() {
+        let (manager, temp_dir) = create_test_manager();
+
+        let missing = manager.verify_model(ModelSize::Tiny).unwrap();
+        assert_eq!(missing.integrity, ModelIntegrity::Missing);
+        assert!(missing.actual_sha256.is_none());
+
+        // A truncated download does not match the published checksum
+        fs::write(temp_dir.path().join("tiny.pt"), b"abc").unwrap();
+        let corrupt = manager.verify_model(ModelSize::Tiny).unwrap();
+        assert_eq!(corrupt.integrity, ModelIntegrity::Corrupt);
+        assert_eq!(
+            corrupt.actual_sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(corrupt.expected_sha256, KNOWN_MODELS[0].sha256);
+    }
+
+    #[test]
+    fn test_delete_model() {
+        let (manager, temp_dir) = create_test_manager();
+        let path = temp_dir.path().join("large-v3.pt");
+        fs::write(&path, b"checkpoint").unwrap();
+
+        // large is an alias of large-v3
+        assert!(manager.model_info(ModelSize::LargeV3).unwrap().downloaded);
+        manager.delete_model(ModelSize::Large).unwrap();
+        assert!(!path.exists());
+        assert!(!manager.model_info(ModelSize::LargeV3).unwrap().downloaded);
+
+        assert!(matches!(manager.delete_model(ModelSize::Large), Err(AppError::ModelNotFound(_))));
+    }
+
+    /// Bytes that look like a PyTorch zip checkpoint
+    fn fake_checkpoint(content: &str) -> Vec<u8> {
+        let mut bytes = b"PK\x03\x04".to_vec();
+        bytes.extend_from_slice(content.as_bytes());
+        bytes
+    }
+
+    /// Files left in the cache directory, ignoring subdirectories
+    fn cache_files(dir: &Path) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_file())
+            .map(|path| file_name(&path))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_custom_checkpoint() {
+        let (manager, temp_dir) = create_test_manager();
+        let source_dir = TempDir::new().unwrap();
+        let source = source_dir.path().join("whisper-tr-meetings.pt");
+        fs::write(&source, fake_checkpoint("fine-tuned")).unwrap();
+
+        let imported = manager.import_model(&source).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, ModelSize::Custom);
+        assert!(imported[0].downloaded);
+        assert_eq!(imported[0].size_on_disk, 14);
+
+        let installed = temp_dir.path().join("custom").join("whisper-tr-meetings.pt");
+        assert_eq!(imported[0].path.as_deref(), Some(installed.to_string_lossy().as_ref()));
+        assert_eq!(fs::read(&installed).unwrap(), fake_checkpoint("fine-tuned"));
+
+        let models = manager.list_models();
+        assert_eq!(models.len(), KNOWN_MODELS.len() + 1);
+        assert_eq!(models.last().unwrap().path, imported[0].path);
+
+        // The imported checkpoint can be selected by its name
+        assert_eq!(manager.custom_model_path("whisper-tr-meetings"), Some(installed.clone()));
+        assert_eq!(manager.custom_model_path("whisper-tr-meetings.pt"), Some(installed));
+        assert_eq!(manager.custom_model_path("whisper-en-meetings"), None);
+        assert_eq!(manager.custom_model_path("../custom/whisper-tr-meetings.pt"), None);
+    }
+
+    #[test]
+    fn test_reimport_custom_checkpoint() {
+        let (manager, temp_dir) = create_test_manager();
+        let source_dir = TempDir::new().unwrap();
+        let source = source_dir.path().join("whisper-tr-meetings.pt");
+        fs::write(&source, fake_checkpoint("fine-tuned")).unwrap();
+        manager.import_model(&source).unwrap();
+
+        // The same checkpoint again is accepted
+        let imported = manager.import_model(&source).unwrap();
+        assert_eq!(imported.len(), 1);
+
+        // A different checkpoint with the same name does not replace it
+        fs::write(&source, fake_checkpoint("fine-tuned v2")).unwrap();
+        assert!(matches!(manager.import_model(&source), Err(AppError::InvalidInput(ref m)) if m.contains("already imported")));
+
+        let installed = temp_dir.path().join("custom").join("whisper-tr-meetings.pt");
+        assert_eq!(fs::read(&installed).unwrap(), fake_checkpoint("fine-tuned"));
+        assert_eq!(manager.list_models().len(), KNOWN_MODELS.len() + 1);
+        assert!(cache_files(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_files() {
+        let (manager, temp_dir) = create_test_manager();
+        let source_dir = TempDir::new().unwrap();
+
+        // Not a PyTorch checkpoint at all
+        let text = source_dir.path().join("notes.pt");
+        fs::write(&text, b"meeting notes").unwrap();
+        assert!(matches!(manager.import_model(&text), Err(AppError::InvalidInput(ref m)) if m.contains("not a Whisper checkpoint")));
+
+        // Named like an official checkpoint, but with the wrong checksum
+        let truncated = source_dir.path().join("small.pt");
+        fs::write(&truncated, fake_checkpoint("half a download")).unwrap();
+        assert!(matches!(manager.import_model(&truncated), Err(AppError::InvalidInput(ref m)) if m.contains("checksum")));
+        assert!(!manager.is_installed(ModelSize::Small));
+
+        let missing = source_dir.path().join("missing.pt");
+        assert!(matches!(manager.import_model(&missing), Err(AppError::FileNotFound(_))));
+
+        // Nothing staged is left behind
+        assert!(cache_files(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_import_directory_and_archive() {
+        let (manager, _temp) = create_test_manager();
+        let source_dir = TempDir::new().unwrap();
+
+        let models_dir = source_dir.path().join("models");
+        fs::create_dir(&models_dir).unwrap();
+        fs::write(models_dir.join("a.pt"), fake_checkpoint("a")).unwrap();
+        fs::write(models_dir.join("b.pt"), fake_checkpoint("b")).unwrap();
+        fs::write(models_dir.join("README.txt"), b"readme").unwrap();
+        let imported = manager.import_model(&models_dir).unwrap();
+        assert_eq!(imported.len(), 2);
+
+        let archive_path = source_dir.path().join("models.zip");
+        let mut archive = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        archive.start_file("whisper/c.pt", options).unwrap();
+        archive.write_all(&fake_checkpoint("c")).unwrap();
+        archive.start_file("whisper/LICENSE", options).unwrap();
+        archive.write_all(b"MIT").unwrap();
+        archive.finish().unwrap();
+
+        let imported = manager.import_model(&archive_path).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert!(imported[0].path.as_deref().unwrap().ends_with("c.pt"));
+        assert_eq!(manager.list_models().len(), KNOWN_MODELS.len() + 3);
+
+        let empty_dir = source_dir.path().join("empty");
+        fs::create_dir(&empty_dir).unwrap();
+        assert!(matches!(manager.import_model(&empty_dir), Err(AppError::InvalidInput(_))));
+    }
+}
@@ -93,6 +93,12 @@ pub struct StoredTranscript {
     pub language: String,
     #[serde(rename = "modelSize")]
     pub model_size: ModelSize,
+    /// Model path or name, when `model_size` is custom
+    #[serde(rename = "customModel", default)]
+    pub custom_model: Option<String>,
+    /// Options the transcript was made with
+    #[serde(default)]
+    pub options: TranscriptionOptions,
     pub segments: Vec<Segment>,
 }
 
@@ -107,6 +113,20 @@ pub enum QueueItemStatus {
     Processing,
     Completed,
     Error,
+    /// Was processing when the app quit or crashed; can be retried
+    Interrupted,
+}
+
+/// Scheduling priority of a queued job
+///
+/// Higher priority jobs are queued ahead of lower priority ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
 }
 
 /// Queue item for batch processing
@@ -121,6 +141,18 @@ pub struct QueueItem {
     pub progress: f64,
     #[serde(rename = "addedAt")]
     pub added_at: String,
+    #[serde(rename = "modelSize", default)]
+    pub model_size: ModelSize,
+    /// Position in seconds where transcription starts (non-zero when resuming)
+    #[serde(rename = "startOffset", default)]
+    pub start_offset: f64,
+    /// Model path or name, when `model_size` is custom
+    #[serde(rename = "customModel", default)]
+    pub custom_model: Option<String>,
+    #[serde(default)]
+    pub options: TranscriptionOptions,
+    #[serde(default)]
+    pub priority: JobPriority,
 }
 
 // ============================================
@@ -130,9 +162,49 @@ pub struct QueueItem {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ModelSize {
+    Tiny,
     Base,
     Small,
     Medium,
+    /// The newest large model
+    Large,
+    #[serde(rename = "large-v2")]
+    LargeV2,
+    #[serde(rename = "large-v3")]
+    LargeV3,
+    /// large-v3-turbo, close to large-v3 accuracy at a fraction of its cost
+    Turbo,
+    /// The model configured as `customModel` in settings
+    Custom,
+}
+
+impl ModelSize {
+    /// Every model size with a name of its own
+    pub const BUILT_IN: [ModelSize; 8] = [
+        ModelSize::Tiny,
+        ModelSize::Base,
+        ModelSize::Small,
+        ModelSize::Medium,
+        ModelSize::Large,
+        ModelSize::LargeV2,
+        ModelSize::LargeV3,
+        ModelSize::Turbo,
+    ];
+
+    /// Name of the model in the whisper-engine protocol; `None` for a custom model
+    pub fn engine_name(self) -> Option<&'static str> {
+        match self {
+            ModelSize::Tiny => Some("tiny"),
+            ModelSize::Base => Some("base"),
+            ModelSize::Small => Some("small"),
+            ModelSize::Medium => Some("medium"),
+            ModelSize::Large => Some("large"),
+            ModelSize::LargeV2 => Some("large-v2"),
+            ModelSize::LargeV3 => Some("large-v3"),
+            ModelSize::Turbo => Some("turbo"),
+            ModelSize::Custom => None,
+        }
+    }
 }
 
 impl Default for ModelSize {
@@ -141,27 +213,174 @@ impl Default for ModelSize {
     }
 }
 
+/// Whether Whisper transcribes speech as spoken or translates it to English
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionTask {
+    #[default]
+    Transcribe,
+    Translate,
+}
+
+/// Device a transcription job runs inference on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeDevice {
+    Cpu,
+    Cuda,
+}
+
+impl ComputeDevice {
+    /// Name of the device in the whisper-engine protocol
+    pub fn engine_name(self) -> &'static str {
+        match self {
+            ComputeDevice::Cpu => "cpu",
+            ComputeDevice::Cuda => "cuda",
+        }
+    }
+}
+
+/// Decoding options of a transcription job
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TranscriptionOptions {
+    /// Language code such as "tr"; `None` detects the language
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub task: TranscriptionTask,
+    /// Text that primes the decoder, such as names and domain vocabulary
+    #[serde(rename = "initialPrompt", default)]
+    pub initial_prompt: Option<String>,
+    /// Sampling temperature from 0 (deterministic) to 1
+    #[serde(default)]
+    pub temperature: f64,
+    /// Inference device; `None` uses the device from settings
+    #[serde(default)]
+    pub device: Option<ComputeDevice>,
+    /// CPU threads; `None` uses the thread count from settings
+    #[serde(default)]
+    pub threads: Option<usize>,
+}
+
+impl TranscriptionOptions {
+    /// Check the options and normalize blank values to their defaults
+    pub fn validate(mut self) -> Result<Self, AppError> {
+        self.language = self
+            .language
+            .map(|code| code.trim().to_lowercase())
+            .filter(|code| !code.is_empty() && code != "auto");
+        if let Some(code) = &self.language {
+            // Whisper language codes are two or three letters, e.g. "tr" or "haw"
+            if !(2..=3).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_lowercase()) {
+                return Err(AppError::InvalidInput(format!("Invalid language code: {}", code)));
+            }
+        }
+
+        self.initial_prompt = self
+            .initial_prompt
+            .map(|prompt| prompt.trim().to_string())
+            .filter(|prompt| !prompt.is_empty());
+
+        if !(0.0..=1.0).contains(&self.temperature) {
+            return Err(AppError::InvalidInput(format!(
+                "Temperature must be between 0 and 1: {}",
+                self.temperature
+            )));
+        }
+
+        if self.threads == Some(0) {
+            return Err(AppError::InvalidInput("Thread count must be at least 1".to_string()));
+        }
+
+        Ok(self)
+    }
+}
+
 /// Application settings
 /// Requirements: 5.1 (export formats), 9.1-9.5
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     #[serde(rename = "modelSize")]
     pub model_size: ModelSize,
+    /// Path to a model checkpoint, such as a fine-tuned one, or the name of
+    /// one imported into the model cache; used by the custom model size
+    #[serde(rename = "customModel", default)]
+    pub custom_model: Option<String>,
     #[serde(rename = "minimizeToTray")]
     pub minimize_to_tray: bool,
     #[serde(rename = "defaultExportFormat")]
     pub default_export_format: ExportFormat,
     #[serde(rename = "autoCheckUpdates")]
     pub auto_check_updates: bool,
+    /// Maximum number of whisper-engine processes running at once
+    #[serde(rename = "maxConcurrentJobs", default = "default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+    /// Seconds without progress before a running job is considered hung;
+    /// extended for long media
+    #[serde(rename = "watchdogTimeoutSecs", default = "default_watchdog_timeout_secs")]
+    pub watchdog_timeout_secs: u64,
+    /// Device jobs run on unless they choose their own; `None` uses CUDA when available
+    #[serde(default)]
+    pub device: Option<ComputeDevice>,
+    /// CPU threads per job unless a job sets its own; `None` uses the job's
+    /// share of the CPU budget
+    #[serde(rename = "cpuThreads", default)]
+    pub cpu_threads: Option<usize>,
+    /// CPU threads all running jobs may use together, split evenly between
+    /// the concurrency slots; `None` uses every core
+    #[serde(rename = "cpuBudget", default)]
+    pub cpu_budget: Option<usize>,
+    /// Whether whisper-engine may download models that are not installed;
+    /// off by default, so jobs for missing models are rejected before they start
+    #[serde(rename = "allowModelDownloads", default)]
+    pub allow_model_downloads: bool,
+    /// Engine that runs transcription jobs
+    #[serde(rename = "transcriptionBackend", default)]
+    pub transcription_backend: BackendSettings,
+    /// Options used for jobs started without their own
+    #[serde(rename = "defaultTranscriptionOptions", default)]
+    pub default_transcription_options: TranscriptionOptions,
+}
+
+/// Transcription engine selected in settings
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BackendSettings {
+    /// The bundled whisper-engine sidecar
+    #[default]
+    WhisperEngine,
+    /// A program that speaks the whisper-engine worker protocol
+    ExternalCommand {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+fn default_max_concurrent_jobs() -> usize {
+    1
+}
+
+fn default_watchdog_timeout_secs() -> u64 {
+    300
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Settings {
             model_size: ModelSize::Base,
+            custom_model: None,
             minimize_to_tray: false,
             default_export_format: ExportFormat::Txt,
             auto_check_updates: true,
+            max_concurrent_jobs: default_max_concurrent_jobs(),
+            watchdog_timeout_secs: default_watchdog_timeout_secs(),
+            device: None,
+            cpu_threads: None,
+            cpu_budget: None,
+            allow_model_downloads: false,
+            transcription_backend: BackendSettings::default(),
+            default_transcription_options: TranscriptionOptions::default(),
         }
     }
 }
@@ -170,15 +389,50 @@ impl Default for Settings {
 // Sidecar Message Types (Python -> Rust -> Frontend)
 // ============================================
 
+/// Engine details announced in the sidecar's `hello` handshake
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineInfo {
+    pub protocol_version: u32,
+    pub engine_version: String,
+    pub models: Vec<String>,
+    pub devices: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum SidecarMessage {
+    Hello(EngineInfo),
     Progress { percent: u32, status: String },
     Segment { data: Segment },
     Complete { language: String, duration: f64 },
     Error { message: String },
 }
 
+// ============================================
+// Engine Request Types (Rust -> Python)
+// ============================================
+
+/// Request written to a whisper-engine worker's stdin, one JSON object per line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum EngineRequest {
+    Transcribe {
+        job_id: String,
+        audio_path: String,
+        model: String,
+        start_offset: f64,
+        /// Language code; `None` detects the language
+        language: Option<String>,
+        task: TranscriptionTask,
+        initial_prompt: Option<String>,
+        temperature: f64,
+        /// Inference device; `None` lets the engine choose
+        device: Option<ComputeDevice>,
+        /// CPU threads; `None` lets the engine choose
+        threads: Option<usize>,
+    },
+}
+
 // ============================================
 // Model Info Types
 // ============================================
@@ -186,8 +440,36 @@ pub enum SidecarMessage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub name: ModelSize,
+    /// Approximate download size, for display
     pub size: String,
     pub downloaded: bool,
+    /// Bytes the checkpoint takes in the model cache; 0 when not downloaded
+    #[serde(rename = "sizeOnDisk", default)]
+    pub size_on_disk: u64,
+    /// Where the checkpoint is, or would be, stored
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Result of checking a downloaded model against its published checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelIntegrity {
+    Valid,
+    /// The file does not match the checksum, e.g. after an interrupted download
+    Corrupt,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVerification {
+    pub name: ModelSize,
+    pub integrity: ModelIntegrity,
+    #[serde(rename = "expectedSha256")]
+    pub expected_sha256: String,
+    /// Checksum of the file on disk, if there is one
+    #[serde(rename = "actualSha256")]
+    pub actual_sha256: Option<String>,
 }
 
 // ============================================
@@ -208,6 +490,10 @@ pub enum AppError {
     SidecarError(String),
     FFmpegNotFound,
     InvalidInput(String),
+    OutOfMemory(String),
+    DecodeFailed(String),
+    ProcessKilled(String),
+    Timeout(String),
 }
 
 impl std::fmt::Display for AppError {
@@ -225,6 +511,10 @@ impl std::fmt::Display for AppError {
                 "FFmpeg not found. Please install FFmpeg or ensure ffmpeg.exe is in the application directory."
             ),
             AppError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            AppError::OutOfMemory(msg) => write!(f, "Out of memory: {}", msg),
+            AppError::DecodeFailed(msg) => write!(f, "Could not decode media: {}", msg),
+            AppError::ProcessKilled(msg) => write!(f, "Transcription process was killed: {}", msg),
+            AppError::Timeout(msg) => write!(f, "Transcription timed out: {}", msg),
         }
     }
 }
@@ -273,6 +563,19 @@ mod tests {
         
         let parsed: ModelSize = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed, ModelSize::Medium);
+
+        assert_eq!(serde_json::to_string(&ModelSize::LargeV3).unwrap(), "\"large-v3\"");
+        assert_eq!(serde_json::from_str::<ModelSize>("\"turbo\"").unwrap(), ModelSize::Turbo);
+    }
+
+    #[test]
+    fn test_model_size_engine_names() {
+        for size in ModelSize::BUILT_IN {
+            let name = size.engine_name().unwrap();
+            // The protocol name is the serialized name
+            assert_eq!(serde_json::to_string(&size).unwrap(), format!("\"{}\"", name));
+        }
+        assert_eq!(ModelSize::Custom.engine_name(), None);
     }
 
     #[test]
@@ -282,6 +585,102 @@ mod tests {
         assert!(!settings.minimize_to_tray);
         assert_eq!(settings.default_export_format, ExportFormat::Txt);
         assert!(settings.auto_check_updates);
+        assert_eq!(settings.max_concurrent_jobs, 1);
+    }
+
+    #[test]
+    fn test_settings_missing_max_concurrent_jobs_uses_default() {
+        let json = r#"{"modelSize":"small","minimizeToTray":true,"defaultExportFormat":"srt","autoCheckUpdates":false}"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.max_concurrent_jobs, 1);
+        assert_eq!(settings.watchdog_timeout_secs, 300);
+        assert_eq!(settings.device, None);
+        assert_eq!(settings.cpu_budget, None);
+        assert!(!settings.allow_model_downloads);
+        assert_eq!(settings.transcription_backend, BackendSettings::WhisperEngine);
+        assert_eq!(settings.default_transcription_options, TranscriptionOptions::default());
+    }
+
+    #[test]
+    fn test_transcription_options_validate() {
+        let options = TranscriptionOptions {
+            language: Some(" TR ".to_string()),
+            task: TranscriptionTask::Translate,
+            initial_prompt: Some("  ".to_string()),
+            temperature: 0.2,
+            device: Some(ComputeDevice::Cpu),
+            threads: Some(4),
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(options.language.as_deref(), Some("tr"));
+        assert_eq!(options.initial_prompt, None);
+
+        let auto = TranscriptionOptions { language: Some("auto".to_string()), ..Default::default() };
+        assert_eq!(auto.validate().unwrap().language, None);
+
+        for language in ["turkish", "t", "t1"] {
+            let options = TranscriptionOptions { language: Some(language.to_string()), ..Default::default() };
+            assert!(matches!(options.validate(), Err(AppError::InvalidInput(_))));
+        }
+        for temperature in [-0.1, 1.5, f64::NAN] {
+            let options = TranscriptionOptions { temperature, ..Default::default() };
+            assert!(matches!(options.validate(), Err(AppError::InvalidInput(_))));
+        }
+        let options = TranscriptionOptions { threads: Some(0), ..Default::default() };
+        assert!(matches!(options.validate(), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_transcription_options_serialization() {
+        let json = r#"{"language":"tr","task":"translate","initialPrompt":"ScriptGrab, Tauri","device":"cuda"}"#;
+        let options: TranscriptionOptions = serde_json::from_str(json).unwrap();
+        assert_eq!(options.task, TranscriptionTask::Translate);
+        assert_eq!(options.initial_prompt.as_deref(), Some("ScriptGrab, Tauri"));
+        assert_eq!(options.temperature, 0.0);
+        assert_eq!(options.device, Some(ComputeDevice::Cuda));
+        assert_eq!(options.threads, None);
+
+        let request = EngineRequest::Transcribe {
+            job_id: "job-1".to_string(),
+            audio_path: "/in/a.mp3".to_string(),
+            model: "base".to_string(),
+            start_offset: 0.0,
+            language: options.language,
+            task: options.task,
+            initial_prompt: options.initial_prompt,
+            temperature: options.temperature,
+            device: options.device,
+            threads: Some(2),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains(r#""task":"translate""#));
+        assert!(json.contains(r#""initial_prompt":"ScriptGrab, Tauri""#));
+        assert!(json.contains(r#""device":"cuda""#));
+        assert!(json.contains(r#""threads":2"#));
+    }
+
+    #[test]
+    fn test_backend_settings_serialization() {
+        let backend = BackendSettings::ExternalCommand {
+            program: "/usr/local/bin/whisper-cpp-worker".to_string(),
+            args: vec!["--threads".to_string(), "4".to_string()],
+        };
+        let json = serde_json::to_string(&backend).unwrap();
+        assert!(json.contains("\"kind\":\"externalCommand\""));
+        assert_eq!(serde_json::from_str::<BackendSettings>(&json).unwrap(), backend);
+
+        let json = r#"{"kind":"externalCommand","program":"worker"}"#;
+        let parsed: BackendSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed, BackendSettings::ExternalCommand { program: "worker".to_string(), args: vec![] });
+    }
+
+    #[test]
+    fn test_queue_item_without_model_size_uses_default() {
+        let json = r#"{"id":"q1","filePath":"/a.mp3","fileName":"a.mp3","status":"interrupted","progress":0.0,"addedAt":"2024-01-01T00:00:00Z"}"#;
+        let item: QueueItem = serde_json::from_str(json).unwrap();
+        assert_eq!(item.status, QueueItemStatus::Interrupted);
+        assert_eq!(item.model_size, ModelSize::Base);
     }
 
     #[test]
@@ -301,4 +700,17 @@ mod tests {
         assert!(json.contains("\"type\":\"progress\""));
         assert!(json.contains("\"percent\":50"));
     }
+
+    #[test]
+    fn test_sidecar_hello_deserialization() {
+        let json = r#"{"type":"hello","protocol_version":1,"engine_version":"0.1.0","models":["base"],"devices":["cpu","cuda"]}"#;
+        let message: SidecarMessage = serde_json::from_str(json).unwrap();
+        match message {
+            SidecarMessage::Hello(info) => {
+                assert_eq!(info.protocol_version, 1);
+                assert_eq!(info.devices, vec!["cpu", "cuda"]);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
 }
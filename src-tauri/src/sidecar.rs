@@ -1,30 +1,274 @@
 //! ScriptGrab Sidecar Management Module
 //! 
-//! Handles spawning, managing, and communicating with the whisper-engine sidecar process.
+//! Queues transcription jobs and runs them on warm worker processes of the
+//! configured backend, by default the whisper-engine sidecar. The manager
+//! outlives the webview, so it also reports progress, saves finished
+//! transcripts to history and persists the queue across restarts.
 //! 
 //! Requirements: 2.3, 2.4, 2.8
 
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tauri::{AppHandle, Emitter};
-use tauri_plugin_shell::{ShellExt, process::CommandChild};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use tokio::sync::{Mutex, Notify};
+use tauri::{AppHandle, Emitter, Runtime};
 use serde::Serialize;
 use uuid::Uuid;
 
-use crate::models::{AppError, ModelSize, Segment, SidecarMessage};
+use crate::backend::{BackendEvent, SidecarOutput, TranscriptionBackend};
+use crate::diagnostics::{classify_failure, ExitStatus, StderrTail};
+use crate::file_handler::{get_file_metadata_internal, get_file_name};
+use crate::model_manager::ModelManager;
+use crate::models::{
+    AppError, BackendSettings, ComputeDevice, EngineInfo, EngineRequest, JobPriority, ModelSize, QueueItem, QueueItemStatus, Segment, Settings, SidecarMessage,
+    StoredTranscript, TranscriptionOptions,
+};
+use crate::storage::{current_timestamp, StorageManager};
+use crate::throughput::{estimate_progress, ProgressEstimate, ThroughputStats};
+use crate::worker::{SharedWorkerState, Worker, WorkerPool};
+
+/// Event name for queue state changes
+pub const QUEUE_CHANGED_EVENT: &str = "queue_changed";
+
+/// Version of the sidecar JSON protocol this app speaks
+pub const SIDECAR_PROTOCOL_VERSION: u32 = 3;
+
+/// How often the watchdog looks for hung jobs
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Extra seconds of watchdog window per second of media
+///
+/// Loading and resampling a long file happens before the first progress
+/// message, so a fixed window would kill healthy jobs on long recordings.
+const WATCHDOG_SECONDS_PER_MEDIA_SECOND: f64 = 0.1;
+
+/// Seconds of audio the bundled engine decodes between two progress messages
+///
+/// Matches `CHUNK_SECONDS` in whisper-engine/chunking.py.
+const ENGINE_CHUNK_SECONDS: f64 = 60.0;
+
+/// Slowest real-time factor the watchdog allows for, that of the largest
+/// models on an old CPU
+const SLOWEST_REAL_TIME_FACTOR: f64 = 0.2;
 
 // ============================================
 // Types
 // ============================================
 
-/// Represents an active transcription job
+/// Lifecycle state of a transcription job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    /// Sidecar process is suspended; still occupies a concurrency slot
+    Paused,
+    /// Was running when the app last quit; waits for an explicit retry
+    Interrupted,
+    /// Sidecar exited with an error; waits for an explicit retry
+    Failed,
+}
+
+impl JobState {
+    /// Whether the job has a sidecar process counting against the concurrency limit
+    pub fn holds_slot(self) -> bool {
+        matches!(self, JobState::Running | JobState::Paused)
+    }
+
+    /// Whether the job still has work scheduled (as opposed to waiting for a retry)
+    pub fn is_active(self) -> bool {
+        matches!(self, JobState::Queued | JobState::Running | JobState::Paused)
+    }
+
+    /// Whether the job can be put back into the queue
+    pub fn is_retryable(self) -> bool {
+        matches!(self, JobState::Interrupted | JobState::Failed)
+    }
+}
+
+/// Represents a queued or active transcription job
 #[derive(Debug)]
 pub struct TranscriptionJob {
     pub id: String,
     pub file_path: String,
     pub model_size: ModelSize,
-    pub child: Option<CommandChild>,
+    /// Model path or name from settings, when `model_size` is custom
+    pub custom_model: Option<String>,
+    pub state: JobState,
+    pub priority: JobPriority,
+    pub added_at: String,
+    /// When the current attempt's sidecar was started
+    pub started_at: Option<DateTime<Utc>>,
+    /// Last progress percent reported by the sidecar
+    pub progress: u32,
+    /// Last status text reported by the sidecar
+    pub status: String,
+    /// Position in seconds where the sidecar starts transcribing
+    pub start_offset: f64,
+    /// Language, task and decoding options
+    pub options: TranscriptionOptions,
+    /// Device the current attempt runs on; `None` lets the engine choose
+    pub device: Option<ComputeDevice>,
+    /// CPU threads the current attempt may use
+    pub threads: Option<usize>,
+    /// Segments received so far, saved to history on completion
+    pub segments: Vec<Segment>,
+    /// End of the last segment kept when the job was resumed; segments of the
+    /// resumed attempt ending before it repeat the earlier attempt
+    pub resume_cutoff: Option<f64>,
+    /// Most recent stderr lines of the current attempt
+    pub stderr: StderrTail,
+    /// Message of an `error` protocol message sent by the current attempt
+    pub engine_error: Option<String>,
+    /// Malformed protocol messages received during the current attempt
+    pub protocol_violations: u32,
+    /// Why the backend killed or failed the current attempt, reported instead
+    /// of the exit status
+    pub abort_reason: Option<AppError>,
+    /// When the running sidecar last reported progress or a segment
+    pub last_activity: Option<Instant>,
+    /// When the current attempt was paused, while it is paused
+    pub paused_at: Option<Instant>,
+    /// Time the current attempt spent paused before its last resume
+    pub paused_for: Duration,
+    /// How long the sidecar may stay silent before the watchdog kills it
+    pub stall_window: Duration,
+    /// Media duration in seconds probed when the current attempt started
+    pub media_duration: Option<f64>,
+    /// Handshake of the current attempt's worker, once received
+    pub engine: Option<EngineInfo>,
+    /// Worker process running the job
+    pub worker: Option<Worker>,
+}
+
+
+impl TranscriptionJob {
+    /// Create a queued job of normal priority that has not run yet
+    fn new(
+        id: String,
+        file_path: String,
+        model_size: ModelSize,
+        custom_model: Option<String>,
+        start_offset: f64,
+        options: TranscriptionOptions,
+    ) -> Self {
+        Self {
+            id,
+            file_path,
+            model_size,
+            custom_model,
+            state: JobState::Queued,
+            priority: JobPriority::default(),
+            added_at: current_timestamp(),
+            started_at: None,
+            progress: 0,
+            status: String::new(),
+            start_offset,
+            options,
+            device: None,
+            threads: None,
+            segments: Vec::new(),
+            resume_cutoff: None,
+            stderr: StderrTail::new(),
+            engine_error: None,
+            protocol_violations: 0,
+            abort_reason: None,
+            last_activity: None,
+            paused_at: None,
+            paused_for: Duration::ZERO,
+            stall_window: Duration::ZERO,
+            media_duration: None,
+            engine: None,
+            worker: None,
+        }
+    }
+
+    /// Model the engine should load for this job
+    fn model(&self) -> String {
+        match self.model_size.engine_name() {
+            Some(name) => name.to_string(),
+            None => self.custom_model.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Build the request that hands this job to a worker
+    fn engine_request(&self) -> EngineRequest {
+        EngineRequest::Transcribe {
+            job_id: self.id.clone(),
+            audio_path: self.file_path.clone(),
+            model: self.model(),
+            start_offset: self.start_offset,
+            language: self.options.language.clone(),
+            task: self.options.task,
+            initial_prompt: self.options.initial_prompt.clone(),
+            temperature: self.options.temperature,
+            device: self.device,
+            threads: self.threads,
+        }
+    }
+
+    /// Kill the job's worker; `reason` is reported once the process exits
+    fn abort(&mut self, reason: AppError) {
+        self.abort_reason = Some(reason);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.kill();
+        }
+    }
+
+    /// Describe the job for status queries
+    fn info(&self, throughput: &ThroughputStats) -> JobInfo {
+        let estimate = self.estimate(throughput);
+        JobInfo {
+            job_id: self.id.clone(),
+            file_path: self.file_path.clone(),
+            file_name: get_file_name(&self.file_path),
+            model_size: self.model_size,
+            state: self.state,
+            priority: self.priority,
+            percent: self.progress,
+            status: self.status.clone(),
+            added_at: self.added_at.clone(),
+            started_at: self.started_at.map(|t| t.to_rfc3339()),
+            elapsed_seconds: self.running_seconds(),
+            eta_seconds: estimate.and_then(|e| e.eta_seconds),
+            real_time_factor: estimate.and_then(|e| e.real_time_factor),
+            segment_count: self.segments.len(),
+            start_offset: self.start_offset,
+        }
+    }
+
+    /// Estimate the time left for the current attempt, if the job is running
+    fn estimate(&self, throughput: &ThroughputStats) -> Option<ProgressEstimate> {
+        if !self.state.holds_slot() {
+            return None;
+        }
+
+        Some(estimate_progress(
+            self.running_seconds()?,
+            self.start_offset,
+            self.segments.last().map(|segment| segment.end),
+            self.media_duration,
+            throughput.real_time_factor(&self.model()),
+        ))
+    }
+
+    /// Seconds the current attempt has been running, excluding time spent paused
+    fn running_seconds(&self) -> Option<f64> {
+        let started_at = self.started_at?;
+        let wall_seconds = (Utc::now() - started_at).num_milliseconds() as f64 / 1000.0;
+        let paused = self.paused_for + self.paused_at.map_or(Duration::ZERO, |t| t.elapsed());
+        Some((wall_seconds - paused.as_secs_f64()).max(0.0))
+    }
+
+    /// Where a retry should pick up: the end of the last received segment
+    fn resume_offset(&self) -> f64 {
+        self.segments
+            .last()
+            .map(|segment| segment.end)
+            .unwrap_or(self.start_offset)
+    }
 }
 
 /// Event payload for transcription progress
@@ -33,6 +277,9 @@ pub struct TranscriptionProgressPayload {
     pub job_id: String,
     pub percent: u32,
     pub status: String,
+    /// Elapsed time, estimated time left and real-time factor
+    #[serde(flatten)]
+    pub estimate: ProgressEstimate,
 }
 
 /// Event payload for transcription segment
@@ -48,6 +295,8 @@ pub struct TranscriptionCompletePayload {
     pub job_id: String,
     pub language: String,
     pub duration: f64,
+    /// ID of the history entry, if the transcript was saved
+    pub transcript_id: Option<String>,
 }
 
 /// Event payload for transcription error
@@ -55,136 +304,716 @@ pub struct TranscriptionCompletePayload {
 pub struct TranscriptionErrorPayload {
     pub job_id: String,
     pub message: String,
+    /// Classified error, for showing a specific explanation
+    pub error: AppError,
+    /// Last lines the sidecar wrote to stderr, oldest first
+    pub stderr_tail: Vec<String>,
+}
+
+impl TranscriptionErrorPayload {
+    pub fn new(job_id: &str, error: AppError, stderr_tail: Vec<String>) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            message: error.to_string(),
+            error,
+            stderr_tail,
+        }
+    }
+}
+
+/// Full status of a single job, for rebuilding the UI without waiting for events
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub job_id: String,
+    pub file_path: String,
+    pub file_name: String,
+    pub model_size: ModelSize,
+    pub state: JobState,
+    pub priority: JobPriority,
+    /// Last progress percent reported by the sidecar
+    pub percent: u32,
+    /// Last status text reported by the sidecar
+    pub status: String,
+    pub added_at: String,
+    /// When the current attempt started, if it has
+    pub started_at: Option<String>,
+    /// Seconds the current attempt has been running, excluding time spent paused
+    pub elapsed_seconds: Option<f64>,
+    /// Estimated seconds until a running job completes
+    pub eta_seconds: Option<f64>,
+    /// Seconds of audio transcribed per wall-clock second in the current attempt
+    pub real_time_factor: Option<f64>,
+    /// Segments received so far, including those kept from earlier attempts
+    pub segment_count: usize,
+    pub start_offset: f64,
+}
+
+/// A single job entry in the queue state snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueEntryPayload {
+    pub job_id: String,
+    pub file_path: String,
+    pub model_size: ModelSize,
+    pub state: JobState,
+    pub priority: JobPriority,
+}
+
+/// Event payload for queue state changes
+///
+/// Running and paused jobs are listed first, followed by queued jobs in
+/// dispatch order and finally interrupted or failed jobs waiting for a retry.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueChangedPayload {
+    pub jobs: Vec<QueueEntryPayload>,
+    pub running: usize,
+    pub paused: usize,
+    pub queued: usize,
+    pub interrupted: usize,
+    pub failed: usize,
+    pub max_concurrent: usize,
 }
 
 // ============================================
 // Sidecar Manager
 // ============================================
 
-/// Manages active transcription jobs and sidecar processes
+/// Manages the job queue and the sidecar processes of running jobs
+///
+/// Cloning is cheap and yields a handle to the same shared state.
+/// When both `jobs` and `queue` are needed, `jobs` is always locked first.
+#[derive(Clone)]
 pub struct SidecarManager {
     jobs: Arc<Mutex<HashMap<String, TranscriptionJob>>>,
+    queue: Arc<Mutex<VecDeque<String>>>,
+    max_concurrent: Arc<AtomicUsize>,
+    dispatch: Arc<Notify>,
+    storage: Option<Arc<StorageManager>>,
+    watchdog_timeout_secs: Arc<AtomicU64>,
+    workers: Arc<Mutex<WorkerPool>>,
+    backend: Arc<RwLock<Option<Arc<dyn TranscriptionBackend>>>>,
+    models: Option<Arc<ModelManager>>,
+    throughput: Arc<Mutex<ThroughputStats>>,
 }
 
 impl SidecarManager {
     pub fn new() -> Self {
+        Self::with_max_concurrent(Settings::default().max_concurrent_jobs)
+    }
+
+    /// Create a manager that runs at most `max_concurrent` sidecars at once
+    pub fn with_max_concurrent(max_concurrent: usize) -> Self {
         Self {
             jobs: Arc::new(Mutex::new(HashMap::new())),
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            max_concurrent: Arc::new(AtomicUsize::new(max_concurrent.max(1))),
+            dispatch: Arc::new(Notify::new()),
+            storage: None,
+            watchdog_timeout_secs: Arc::new(AtomicU64::new(Settings::default().watchdog_timeout_secs)),
+            workers: Arc::new(Mutex::new(WorkerPool::new())),
+            backend: Arc::new(RwLock::new(None)),
+            models: None,
+            throughput: Arc::new(Mutex::new(ThroughputStats::default())),
         }
     }
 
+    /// Set the base number of seconds a running sidecar may go without progress
+    pub fn with_watchdog_timeout(self, seconds: u64) -> Self {
+        self.set_watchdog_timeout(seconds);
+        self
+    }
+
+    /// Run jobs on workers started by the given backend
+    pub fn with_backend(self, backend: Arc<dyn TranscriptionBackend>) -> Self {
+        *self.backend.write().unwrap_or_else(PoisonError::into_inner) = Some(backend);
+        self
+    }
+
+    /// Check jobs against the models installed in the given cache
+    pub fn with_model_manager(mut self, models: ModelManager) -> Self {
+        self.models = Some(Arc::new(models));
+        self
+    }
+
+    /// Persist the queue through the given storage manager on every change
+    ///
+    /// Pass the app's shared storage manager, so finished transcripts and
+    /// history commands update the index under the same lock.
+    pub fn with_storage(mut self, storage: impl Into<Arc<StorageManager>>) -> Self {
+        let storage = storage.into();
+        let throughput = storage.load_throughput().unwrap_or_default();
+        self.throughput = Arc::new(Mutex::new(throughput));
+        self.storage = Some(storage);
+        self
+    }
+
     /// Generate a new unique job ID
     pub fn generate_job_id() -> String {
         Uuid::new_v4().to_string()
     }
 
-    /// Start a new transcription job
-    /// 
-    /// Spawns the whisper-engine sidecar and sets up stdout/stderr handling.
-    /// Emits events to the frontend for progress, segments, completion, and errors.
-    pub async fn start_transcription(
+    /// Get the maximum number of concurrently running sidecars
+    pub fn max_concurrent_jobs(&self) -> usize {
+        self.max_concurrent.load(Ordering::SeqCst)
+    }
+
+    /// Change the concurrency limit
+    ///
+    /// Raising the limit starts queued jobs right away; lowering it lets
+    /// running jobs finish and only affects what is dispatched next. Idle
+    /// workers beyond the new limit are stopped by the next watchdog pass.
+    pub fn set_max_concurrent_jobs(&self, max_concurrent: usize) {
+        self.max_concurrent.store(max_concurrent.max(1), Ordering::SeqCst);
+        self.dispatch.notify_one();
+    }
+
+    /// Get the base number of seconds a running sidecar may go without progress
+    pub fn watchdog_timeout(&self) -> Duration {
+        Duration::from_secs(self.watchdog_timeout_secs.load(Ordering::SeqCst))
+    }
+
+    /// Change the base watchdog window of jobs started from now on
+    pub fn set_watchdog_timeout(&self, seconds: u64) {
+        self.watchdog_timeout_secs.store(seconds.max(1), Ordering::SeqCst);
+    }
+
+    /// Run jobs started from now on on workers of a different backend
+    ///
+    /// Idle workers of the previous backend are stopped; running jobs finish
+    /// on theirs, which are stopped afterwards.
+    pub async fn set_backend(&self, backend: Arc<dyn TranscriptionBackend>) {
+        *self.backend.write().unwrap_or_else(PoisonError::into_inner) = Some(backend);
+
+        for worker in self.jobs.lock().await.values_mut().filter_map(|job| job.worker.as_mut()) {
+            worker.retire();
+        }
+        self.workers.lock().await.clear();
+    }
+
+    /// Apply changed settings to the running manager
+    ///
+    /// The concurrency limit takes effect right away and the watchdog timeout
+    /// for jobs started from now on. A different backend is only started when
+    /// `backend` is given, so warm workers survive unrelated settings changes.
+    pub async fn apply_settings(&self, settings: &Settings, backend: Option<Arc<dyn TranscriptionBackend>>) {
+        self.set_max_concurrent_jobs(settings.max_concurrent_jobs);
+        self.set_watchdog_timeout(settings.watchdog_timeout_secs);
+        if let Some(backend) = backend {
+            self.set_backend(backend).await;
+        }
+    }
+
+    /// Start the background dispatcher that moves queued jobs into free slots
+    ///
+    /// Must be called once during app setup; queued jobs do not start without it.
+    pub fn start_dispatcher<R: Runtime>(&self, app: AppHandle<R>) {
+        let manager = self.clone();
+        tauri::async_runtime::spawn(async move {
+            // Pick up jobs restored from a previous session
+            manager.fill_free_slots(&app).await;
+
+            loop {
+                manager.dispatch.notified().await;
+                manager.fill_free_slots(&app).await;
+            }
+        });
+
+        let manager = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(WATCHDOG_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let now = Instant::now();
+                manager.abort_stalled_jobs(now).await;
+                manager.workers.lock().await.reap(now, manager.max_concurrent_jobs());
+            }
+        });
+    }
+
+    /// Queue a single file for transcription
+    ///
+    /// Transcription begins at `start_offset` seconds into the file. Without
+    /// `options`, the default options from settings are used. The job starts
+    /// as soon as a slot is free. Returns the job ID immediately.
+    pub async fn start_transcription<R: Runtime>(
         &self,
-        app: AppHandle,
+        app: AppHandle<R>,
         file_path: String,
         model_size: ModelSize,
+        start_offset: f64,
+        options: Option<TranscriptionOptions>,
     ) -> Result<String, AppError> {
-        let job_id = Self::generate_job_id();
-        
-        // Validate file exists
-        if !std::path::Path::new(&file_path).exists() {
-            return Err(AppError::FileNotFound(file_path));
+        if !start_offset.is_finite() || start_offset < 0.0 {
+            return Err(AppError::InvalidInput(format!("Invalid start offset: {}", start_offset)));
+        }
+
+        let mut job_ids = self.enqueue(app, vec![file_path], model_size, start_offset, options).await?;
+        Ok(job_ids.remove(0))
+    }
+
+    /// Queue several files for transcription in the given order
+    ///
+    /// All files are validated before anything is queued, so a missing file
+    /// rejects the whole batch. All jobs share the same options. Returns the
+    /// job IDs in the same order.
+    pub async fn enqueue_transcriptions<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        file_paths: Vec<String>,
+        model_size: ModelSize,
+        options: Option<TranscriptionOptions>,
+    ) -> Result<Vec<String>, AppError> {
+        self.enqueue(app, file_paths, model_size, 0.0, options).await
+    }
+
+    async fn enqueue<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        file_paths: Vec<String>,
+        model_size: ModelSize,
+        start_offset: f64,
+        options: Option<TranscriptionOptions>,
+    ) -> Result<Vec<String>, AppError> {
+        if file_paths.is_empty() {
+            return Err(AppError::InvalidInput("No files to transcribe".to_string()));
+        }
+
+        // Validate files exist
+        if let Some(missing) = file_paths.iter().find(|p| !std::path::Path::new(p).exists()) {
+            return Err(AppError::FileNotFound(missing.clone()));
         }
 
-        // Get model size string
-        let model_str = match model_size {
-            ModelSize::Base => "base",
-            ModelSize::Small => "small",
-            ModelSize::Medium => "medium",
+        let settings = self.settings();
+        let custom_model = match model_size {
+            ModelSize::Custom => Some(resolve_custom_model(settings.custom_model.as_deref(), self.models.as_deref())?),
+            _ => None,
         };
+        self.check_model_installed(&settings, model_size)?;
+        let options = options.unwrap_or(settings.default_transcription_options).validate()?;
 
-        // Create the sidecar command
-        let shell = app.shell();
-        let sidecar_command = shell
-            .sidecar("whisper-engine")
-            .map_err(|e| AppError::SidecarError(format!("Failed to create sidecar command: {}", e)))?
-            .args([&file_path, "--model", model_str]);
+        let job_ids = self.push_jobs(file_paths, model_size, custom_model, start_offset, options).await;
 
-        // Spawn the sidecar process
-        let (mut rx, child) = sidecar_command
-            .spawn()
-            .map_err(|e| AppError::SidecarError(format!("Failed to spawn sidecar: {}", e)))?;
+        self.emit_queue_changed(&app).await;
+        self.dispatch.notify_one();
 
-        // Store the job
-        {
-            let mut jobs = self.jobs.lock().await;
-            jobs.insert(job_id.clone(), TranscriptionJob {
-                id: job_id.clone(),
-                file_path: file_path.clone(),
-                model_size,
-                child: Some(child),
-            });
+        Ok(job_ids)
+    }
+
+    /// Reject a built-in model that whisper-engine would have to download
+    /// when downloads are turned off, which is the default
+    ///
+    /// Without this, the engine only fails once it tries to reach the network,
+    /// which looks like a crash. Other backends manage their own models.
+    fn check_model_installed(&self, settings: &Settings, model_size: ModelSize) -> Result<(), AppError> {
+        let Some(models) = &self.models else {
+            return Ok(());
+        };
+        if settings.allow_model_downloads || settings.transcription_backend != BackendSettings::WhisperEngine {
+            return Ok(());
+        }
+
+        match model_size.engine_name() {
+            Some(name) if !models.is_installed(model_size) => Err(AppError::ModelNotFound(format!(
+                "The {} model is not installed; import it from the model manager or allow model downloads in settings",
+                name
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Load the current settings, or the defaults without storage
+    fn settings(&self) -> Settings {
+        self.storage
+            .as_ref()
+            .and_then(|storage| storage.load_settings().ok())
+            .unwrap_or_default()
+    }
+
+    /// Add jobs behind the queued jobs of the same or a higher priority without dispatching them
+    async fn push_jobs(
+        &self,
+        file_paths: Vec<String>,
+        model_size: ModelSize,
+        custom_model: Option<String>,
+        start_offset: f64,
+        options: TranscriptionOptions,
+    ) -> Vec<String> {
+        let mut jobs = self.jobs.lock().await;
+        let mut queue = self.queue.lock().await;
+
+        let mut job_ids = Vec::with_capacity(file_paths.len());
+        for file_path in file_paths {
+            let job_id = Self::generate_job_id();
+            jobs.insert(
+                job_id.clone(),
+                TranscriptionJob::new(
+                    job_id.clone(),
+                    file_path,
+                    model_size,
+                    custom_model.clone(),
+                    start_offset,
+                    options.clone(),
+                ),
+            );
+            insert_by_priority(&mut queue, &jobs, job_id.clone());
+            job_ids.push(job_id);
+        }
+        job_ids
+    }
+
+    /// Start queued jobs until the concurrency limit is reached
+    async fn fill_free_slots<R: Runtime>(&self, app: &AppHandle<R>) {
+        let mut started_any = false;
+        let settings = self.settings();
+
+        loop {
+            let next = {
+                let mut jobs = self.jobs.lock().await;
+                let occupied = jobs.values().filter(|j| j.state.holds_slot()).count();
+                let max_concurrent = self.max_concurrent_jobs();
+                if occupied >= max_concurrent {
+                    break;
+                }
+
+                let mut queue = self.queue.lock().await;
+                let Some(job_id) = queue.pop_front() else {
+                    break;
+                };
+
+                match jobs.get_mut(&job_id) {
+                    Some(job) => {
+                        job.state = JobState::Running;
+                        job.started_at = Some(Utc::now());
+                        job.paused_at = None;
+                        job.paused_for = Duration::ZERO;
+                        job.stderr.clear();
+                        job.engine_error = None;
+                        job.protocol_violations = 0;
+                        job.abort_reason = None;
+                        job.engine = None;
+                        let (device, threads) = compute_allocation(&settings, &job.options, max_concurrent);
+                        job.device = device;
+                        job.threads = Some(threads);
+                        (job_id, job.engine_request())
+                    }
+                    None => continue,
+                }
+            };
+
+            let (job_id, request) = next;
+            started_any = true;
+
+            if let Err(e) = self.start_job(app, &job_id, request).await {
+                self.fail_job(&job_id).await;
+                let _ = app.emit("transcription_error", TranscriptionErrorPayload::new(&job_id, e, Vec::new()));
+            }
+        }
+
+        if started_any {
+            self.emit_queue_changed(app).await;
+        }
+    }
+
+    /// Hand a job that was marked as running to a worker
+    ///
+    /// Reuses an idle worker, so its loaded model is kept, or starts a new one.
+    async fn start_job<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        job_id: &str,
+        request: EngineRequest,
+    ) -> Result<(), AppError> {
+        let media_duration = self.probe_media_duration(job_id).await;
+
+        let pooled = self.workers.lock().await.take();
+        let mut worker = match pooled {
+            Some(worker) => worker,
+            None => self.spawn_worker(app)?,
+        };
+
+        let mut jobs = self.jobs.lock().await;
+        let Some(job) = jobs.get_mut(job_id) else {
+            // Cancelled while the worker was being prepared
+            drop(jobs);
+            self.workers.lock().await.put(worker, self.max_concurrent_jobs());
+            return Ok(());
+        };
+
+        let engine = match worker.start(job_id, &request) {
+            Ok(engine) => engine,
+            Err(e) => {
+                let _ = worker.kill();
+                return Err(e);
+            }
+        };
+        job.worker = Some(worker);
+        job.stall_window = watchdog_window(self.watchdog_timeout(), media_duration);
+        job.media_duration = Some(media_duration).filter(|&d| d > 0.0);
+        job.last_activity = Some(Instant::now());
+
+        // A warm worker sent its handshake long ago; check it against this job
+        if let Some(engine) = engine {
+            match check_engine_compatibility(&engine, &job.model(), job.device) {
+                Ok(()) => job.engine = Some(engine),
+                Err(e) => job.abort(e),
+            }
         }
 
+        Ok(())
+    }
+
+    /// Start a worker process of the configured backend
+    ///
+    /// Sets up event handling that routes the worker's output to its
+    /// current job. When the process exits, a job it was still running fails
+    /// and the next dispatch starts a fresh worker.
+    fn spawn_worker<R: Runtime>(&self, app: &AppHandle<R>) -> Result<Worker, AppError> {
+        let backend = self
+            .backend
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .ok_or_else(|| AppError::SidecarError("No transcription backend configured".to_string()))?;
+        let (process, mut rx) = backend.spawn()?;
+
+        let state = SharedWorkerState::default();
+        let worker = Worker::new(process, state.clone());
+
         // Clone values for the async task
-        let job_id_clone = job_id.clone();
         let app_clone = app.clone();
-        let jobs_clone = self.jobs.clone();
+        let manager = self.clone();
 
-        // Spawn a task to handle sidecar output
+        // Spawn a task to handle worker events
         tauri::async_runtime::spawn(async move {
-            use tauri_plugin_shell::process::CommandEvent;
-
             while let Some(event) = rx.recv().await {
                 match event {
-                    CommandEvent::Stdout(line) => {
-                        let line_str = String::from_utf8_lossy(&line);
-                        if let Err(e) = handle_sidecar_output(&app_clone, &job_id_clone, &line_str) {
-                            eprintln!("Error handling sidecar output: {}", e);
-                        }
+                    BackendEvent::Output(output) => {
+                        manager.handle_worker_output(&app_clone, &state, output).await;
                     }
-                    CommandEvent::Stderr(line) => {
-                        let line_str = String::from_utf8_lossy(&line);
-                        eprintln!("Sidecar stderr: {}", line_str);
+                    BackendEvent::Stderr(line) => {
+                        if let Some(job_id) = state.job_id() {
+                            if let Some(job) = manager.jobs.lock().await.get_mut(&job_id) {
+                                job.stderr.push(&line);
+                            }
+                        }
                     }
-                    CommandEvent::Error(error) => {
-                        let _ = app_clone.emit("transcription_error", TranscriptionErrorPayload {
-                            job_id: job_id_clone.clone(),
-                            message: error.clone(),
-                        });
+                    BackendEvent::Error(error) => {
+                        if let Some(job_id) = state.job_id() {
+                            let stderr_tail = manager.stderr_tail(&job_id).await;
+                            let _ = app_clone.emit("transcription_error", TranscriptionErrorPayload::new(
+                                &job_id,
+                                AppError::SidecarError(error),
+                                stderr_tail,
+                            ));
+                        }
                     }
-                    CommandEvent::Terminated(payload) => {
-                        // Clean up the job when process terminates
-                        let mut jobs = jobs_clone.lock().await;
-                        jobs.remove(&job_id_clone);
-                        
-                        // If terminated with non-zero exit code, emit error
-                        if let Some(code) = payload.code {
-                            if code != 0 {
-                                let _ = app_clone.emit("transcription_error", TranscriptionErrorPayload {
-                                    job_id: job_id_clone.clone(),
-                                    message: format!("Process exited with code: {}", code),
-                                });
+                    BackendEvent::Terminated(exit) => {
+                        manager.workers.lock().await.remove(&state);
+
+                        // Keep an unfinished job and its segments around for a retry
+                        if let Some(job_id) = state.job_id() {
+                            if let Some(failure) = manager.diagnose_failure(&job_id, Some(exit)).await {
+                                manager.fail_job(&job_id).await;
+                                let _ = app_clone.emit("transcription_error", failure);
                             }
                         }
+
+                        // Free the slot for the next queued job
+                        manager.emit_queue_changed(&app_clone).await;
+                        manager.dispatch.notify_one();
+                        break;
                     }
-                    _ => {}
                 }
             }
         });
 
-        Ok(job_id)
+        Ok(worker)
+    }
+
+    /// Route a line of worker output to the job the worker is running
+    async fn handle_worker_output<R: Runtime>(&self, app: &AppHandle<R>, state: &SharedWorkerState, output: SidecarOutput) {
+        // Record the handshake and read the current job in one step, so a job
+        // handed over concurrently either sees the handshake or receives it
+        let job_id = {
+            let mut state = state.lock();
+            if let SidecarOutput::Message(SidecarMessage::Hello(info)) = &output {
+                state.engine = Some(info.clone());
+            }
+            state.job_id.clone()
+        };
+
+        // Output between jobs, such as the handshake of a fresh worker
+        let Some(job_id) = job_id else {
+            return;
+        };
+
+        match self.handle_sidecar_output(app, &job_id, output).await {
+            Ok(Some(end)) => self.end_worker_job(app, &job_id, end).await,
+            Ok(None) => {}
+            Err(e) => eprintln!("Error handling sidecar output: {}", e),
+        }
+    }
+
+    /// Settle a job whose worker reported its end and return the worker to the pool
+    async fn end_worker_job<R: Runtime>(&self, app: &AppHandle<R>, job_id: &str, end: JobEnd) {
+        let failure = match end {
+            JobEnd::Completed => None,
+            JobEnd::Failed => self.diagnose_failure(job_id, None).await,
+        };
+
+        let worker = match self.jobs.lock().await.get_mut(job_id) {
+            Some(job) => job.worker.take(),
+            None => return,
+        };
+
+        match failure {
+            None => self.finish_job(job_id).await,
+            Some(failure) => {
+                // Keep the job and its segments around for a retry
+                self.fail_job(job_id).await;
+                let _ = app.emit("transcription_error", failure);
+            }
+        }
+
+        if let Some(mut worker) = worker {
+            worker.release();
+            self.workers.lock().await.put(worker, self.max_concurrent_jobs());
+        }
+
+        // Free the slot for the next queued job
+        self.emit_queue_changed(app).await;
+        self.dispatch.notify_one();
+    }
+
+    /// Probe the duration of a job's media file with FFprobe
+    ///
+    /// It extends the watchdog window and is the basis of time estimates.
+    /// Returns 0 for files that cannot be probed.
+    async fn probe_media_duration(&self, job_id: &str) -> f64 {
+        let file_path = match self.jobs.lock().await.get(job_id) {
+            Some(job) => job.file_path.clone(),
+            None => return 0.0,
+        };
+
+        tauri::async_runtime::spawn_blocking(move || {
+            get_file_metadata_internal(&file_path)
+                .map(|info| info.duration)
+                .unwrap_or(0.0)
+        })
+        .await
+        .unwrap_or(0.0)
+    }
+
+    /// Kill workers of running jobs that have been silent longer than their window
+    ///
+    /// The timeout is reported when the process exits. Paused jobs are not
+    /// checked. Returns the IDs of the killed jobs.
+    async fn abort_stalled_jobs(&self, now: Instant) -> Vec<String> {
+        let mut jobs = self.jobs.lock().await;
+        let mut aborted = Vec::new();
+
+        for (job_id, job) in jobs.iter_mut() {
+            if job.state != JobState::Running || job.abort_reason.is_some() {
+                continue;
+            }
+            let Some(last_activity) = job.last_activity else {
+                continue;
+            };
+            if now.saturating_duration_since(last_activity) <= job.stall_window {
+                continue;
+            }
+
+            let window = job.stall_window.as_secs();
+            job.abort(AppError::Timeout(format!(
+                "No progress for {} seconds; the engine appears to be hung",
+                window
+            )));
+            aborted.push(job_id.clone());
+        }
+
+        aborted
+    }
+
+    /// Get the buffered stderr lines of a job
+    async fn stderr_tail(&self, job_id: &str) -> Vec<String> {
+        self.jobs
+            .lock()
+            .await
+            .get(job_id)
+            .map(|job| job.stderr.lines())
+            .unwrap_or_default()
+    }
+
+    /// Work out why a job failed
+    ///
+    /// `exit` is set when the worker process exited during the job, and is
+    /// `None` when the worker reported an error and lives on. Returns `None`
+    /// for jobs that are no longer tracked because they were cancelled.
+    async fn diagnose_failure(&self, job_id: &str, exit: Option<ExitStatus>) -> Option<TranscriptionErrorPayload> {
+        let jobs = self.jobs.lock().await;
+        let job = jobs.get(job_id)?;
+
+        let stderr_tail = job.stderr.lines();
+        let error = match &job.abort_reason {
+            Some(reason) => reason.clone(),
+            None if job.engine_error.is_none() && exit.is_some_and(|e| e.code == Some(0)) => {
+                AppError::TranscriptionFailed("Engine exited before the transcription finished".to_string())
+            }
+            None => classify_failure(exit, job.engine_error.as_deref(), &stderr_tail),
+        };
+        Some(TranscriptionErrorPayload::new(job_id, error, stderr_tail))
+    }
+
+    /// Stop tracking a job that completed
+    async fn finish_job(&self, job_id: &str) {
+        self.jobs.lock().await.remove(job_id);
+        self.discard_partial_segments(job_id);
+    }
+
+    /// Mark a job as failed, keeping its segments so a retry can resume
+    ///
+    /// Jobs that were cancelled in the meantime are no longer tracked and stay gone.
+    async fn fail_job(&self, job_id: &str) {
+        let segments = {
+            let mut jobs = self.jobs.lock().await;
+            let Some(job) = jobs.get_mut(job_id) else {
+                return;
+            };
+            job.state = JobState::Failed;
+            job.worker = None;
+            job.segments.clone()
+        };
+
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.save_partial_segments(job_id, &segments) {
+                eprintln!("Failed to save partial segments for job {}: {}", job_id, e);
+            }
+        }
+    }
+
+    fn discard_partial_segments(&self, job_id: &str) {
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.delete_partial_segments(job_id) {
+                eprintln!("Failed to delete partial segments for job {}: {}", job_id, e);
+            }
+        }
     }
 
-    /// Cancel an active transcription job
+    /// Cancel a queued, running or failed transcription job
     /// 
-    /// Kills the sidecar process and removes the job from tracking.
+    /// Kills the worker running the job (if any) and removes the job from tracking.
+    /// The worker cannot stop mid-file, so the next job starts a fresh one.
     pub async fn cancel_transcription(&self, job_id: &str) -> Result<(), AppError> {
         let mut jobs = self.jobs.lock().await;
         
         if let Some(mut job) = jobs.remove(job_id) {
-            if let Some(child) = job.child.take() {
-                child.kill()
-                    .map_err(|e| AppError::SidecarError(format!("Failed to kill process: {}", e)))?;
+            self.queue.lock().await.retain(|id| id != job_id);
+            self.discard_partial_segments(job_id);
+            if let Some(worker) = job.worker.take() {
+                // Wake a paused process tree first so no suspended children are left behind
+                if let (JobState::Paused, Some(pid)) = (job.state, worker.pid()) {
+                    let _ = set_process_tree_suspended(pid, false);
+                }
+                worker.kill()?;
             }
             Ok(())
         } else {
@@ -192,79 +1021,883 @@ impl SidecarManager {
         }
     }
 
-    /// Check if a job is currently active
-    pub async fn is_job_active(&self, job_id: &str) -> bool {
-        let jobs = self.jobs.lock().await;
-        jobs.contains_key(job_id)
+    /// Suspend the sidecar of a running job
+    ///
+    /// The job keeps its concurrency slot while paused.
+    pub async fn pause_transcription(&self, job_id: &str) -> Result<(), AppError> {
+        self.set_job_paused(job_id, true).await
     }
 
-    /// Get the number of active jobs
-    pub async fn active_job_count(&self) -> usize {
-        let jobs = self.jobs.lock().await;
-        jobs.len()
+    /// Continue a paused job where it stopped
+    pub async fn resume_transcription(&self, job_id: &str) -> Result<(), AppError> {
+        self.set_job_paused(job_id, false).await
     }
-}
 
-impl Default for SidecarManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    async fn set_job_paused(&self, job_id: &str, pause: bool) -> Result<(), AppError> {
+        let mut jobs = self.jobs.lock().await;
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| AppError::SidecarError(format!("Job not found: {}", job_id)))?;
 
-// ============================================
-// Output Handling
-// ============================================
+        let (from, to) = if pause {
+            (JobState::Running, JobState::Paused)
+        } else {
+            (JobState::Paused, JobState::Running)
+        };
+        if job.state != from {
+            return Err(AppError::InvalidInput(format!(
+                "Job {} is not {}",
+                job_id,
+                if pause { "running" } else { "paused" }
+            )));
+        }
 
-/// Parse and handle a line of output from the sidecar process
-fn handle_sidecar_output(
-    app: &AppHandle,
-    job_id: &str,
-    line: &str,
-) -> Result<(), AppError> {
-    let line = line.trim();
-    if line.is_empty() {
-        return Ok(());
-    }
+        let pid = job
+            .worker
+            .as_ref()
+            .ok_or_else(|| AppError::SidecarError(format!("Job has no sidecar process: {}", job_id)))?
+            .pid()
+            .ok_or_else(|| AppError::InvalidInput("The transcription backend cannot pause jobs".to_string()))?;
 
-    // Parse the JSON message
-    let message: SidecarMessage = serde_json::from_str(line)
-        .map_err(|e| AppError::SidecarError(format!("Failed to parse sidecar output: {} - Line: {}", e, line)))?;
-
-    match message {
-        SidecarMessage::Progress { percent, status } => {
-            app.emit("transcription_progress", TranscriptionProgressPayload {
-                job_id: job_id.to_string(),
-                percent,
-                status,
-            }).map_err(|e| AppError::SidecarError(format!("Failed to emit progress: {}", e)))?;
-        }
-        SidecarMessage::Segment { data } => {
-            app.emit("transcription_segment", TranscriptionSegmentPayload {
-                job_id: job_id.to_string(),
-                segment: data,
-            }).map_err(|e| AppError::SidecarError(format!("Failed to emit segment: {}", e)))?;
-        }
-        SidecarMessage::Complete { language, duration } => {
-            app.emit("transcription_complete", TranscriptionCompletePayload {
-                job_id: job_id.to_string(),
-                language,
-                duration,
-            }).map_err(|e| AppError::SidecarError(format!("Failed to emit complete: {}", e)))?;
-        }
-        SidecarMessage::Error { message } => {
-            app.emit("transcription_error", TranscriptionErrorPayload {
-                job_id: job_id.to_string(),
-                message,
-            }).map_err(|e| AppError::SidecarError(format!("Failed to emit error: {}", e)))?;
+        set_process_tree_suspended(pid, pause)?;
+        job.state = to;
+        if pause {
+            job.paused_at = Some(Instant::now());
+        } else {
+            // Time spent paused does not count towards the watchdog window or the job's speed
+            job.last_activity = Some(Instant::now());
+            if let Some(paused_at) = job.paused_at.take() {
+                job.paused_for += paused_at.elapsed();
+            }
         }
+        Ok(())
     }
 
-    Ok(())
-}
+    /// Put an interrupted or failed job back in the queue, behind the jobs
+    /// of the same or a higher priority
+    ///
+    /// Segments already received are kept and the sidecar only transcribes
+    /// what comes after the last of them.
+    pub async fn retry_transcription(&self, job_id: &str) -> Result<(), AppError> {
+        {
+            let mut jobs = self.jobs.lock().await;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| AppError::SidecarError(format!("Job not found: {}", job_id)))?;
 
-// ============================================
-// Tests
-// ============================================
+            if !job.state.is_retryable() {
+                return Err(AppError::InvalidInput(format!("Job cannot be retried: {}", job_id)));
+            }
+
+            job.start_offset = job.resume_offset();
+            job.resume_cutoff = job.segments.last().map(|segment| segment.end);
+            job.state = JobState::Queued;
+            insert_by_priority(&mut *self.queue.lock().await, &jobs, job_id.to_string());
+        }
+
+        self.dispatch.notify_one();
+        Ok(())
+    }
+
+    /// Change the priority of a job
+    ///
+    /// A queued job moves behind the queued jobs of the same or a higher
+    /// priority. Running jobs keep running; their priority applies when they
+    /// are retried.
+    pub async fn set_job_priority(&self, job_id: &str, priority: JobPriority) -> Result<(), AppError> {
+        let mut jobs = self.jobs.lock().await;
+        let job = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| AppError::SidecarError(format!("Job not found: {}", job_id)))?;
+        if job.priority == priority {
+            return Ok(());
+        }
+        job.priority = priority;
+
+        if job.state == JobState::Queued {
+            let mut queue = self.queue.lock().await;
+            queue.retain(|id| id != job_id);
+            insert_by_priority(&mut queue, &jobs, job_id.to_string());
+        }
+        Ok(())
+    }
+
+    /// Move a queued job to `position` in dispatch order, 0 being next
+    ///
+    /// Positions past the end move the job to the back of the queue. The
+    /// job keeps its priority, so the user can order jobs freely.
+    pub async fn move_job(&self, job_id: &str, position: usize) -> Result<(), AppError> {
+        let jobs = self.jobs.lock().await;
+        let job = jobs
+            .get(job_id)
+            .ok_or_else(|| AppError::SidecarError(format!("Job not found: {}", job_id)))?;
+        if job.state != JobState::Queued {
+            return Err(AppError::InvalidInput(format!("Only queued jobs can be moved: {}", job_id)));
+        }
+
+        let mut queue = self.queue.lock().await;
+        queue.retain(|id| id != job_id);
+        queue.insert(position.min(queue.len()), job_id.to_string());
+        Ok(())
+    }
+
+    /// Move a queued job to the front of the queue, so it starts in the next free slot
+    pub async fn bump_job(&self, job_id: &str) -> Result<(), AppError> {
+        self.move_job(job_id, 0).await
+    }
+
+    /// Check if a job is currently queued or running
+    pub async fn is_job_active(&self, job_id: &str) -> bool {
+        let jobs = self.jobs.lock().await;
+        jobs.get(job_id).is_some_and(|j| j.state.is_active())
+    }
+
+    /// Get the number of queued and running jobs
+    pub async fn active_job_count(&self) -> usize {
+        let jobs = self.jobs.lock().await;
+        jobs.values().filter(|j| j.state.is_active()).count()
+    }
+
+    /// Get the status of every tracked job, in queue order
+    pub async fn list_jobs(&self) -> Vec<JobInfo> {
+        let jobs = self.jobs.lock().await;
+        let queue = self.queue.lock().await;
+        let throughput = self.throughput.lock().await;
+        ordered_jobs(&jobs, &queue).into_iter().map(|job| job.info(&throughput)).collect()
+    }
+
+    /// Get the status of a single job
+    pub async fn get_job(&self, job_id: &str) -> Result<JobInfo, AppError> {
+        let jobs = self.jobs.lock().await;
+        let throughput = self.throughput.lock().await;
+        jobs.get(job_id)
+            .map(|job| job.info(&throughput))
+            .ok_or_else(|| AppError::SidecarError(format!("Job not found: {}", job_id)))
+    }
+
+    /// Get the number of jobs with a running sidecar
+    pub async fn running_job_count(&self) -> usize {
+        let jobs = self.jobs.lock().await;
+        jobs.values().filter(|j| j.state == JobState::Running).count()
+    }
+
+    /// Build a snapshot of the current queue state
+    pub async fn queue_snapshot(&self) -> QueueChangedPayload {
+        let jobs = self.jobs.lock().await;
+        let queue = self.queue.lock().await;
+
+        let entries: Vec<QueueEntryPayload> = ordered_jobs(&jobs, &queue)
+            .into_iter()
+            .map(|job| QueueEntryPayload {
+                job_id: job.id.clone(),
+                file_path: job.file_path.clone(),
+                model_size: job.model_size,
+                state: job.state,
+                priority: job.priority,
+            })
+            .collect();
+
+        let count = |state: JobState| entries.iter().filter(|e| e.state == state).count();
+        QueueChangedPayload {
+            running: count(JobState::Running),
+            paused: count(JobState::Paused),
+            queued: count(JobState::Queued),
+            interrupted: count(JobState::Interrupted),
+            failed: count(JobState::Failed),
+            jobs: entries,
+            max_concurrent: self.max_concurrent_jobs(),
+        }
+    }
+
+    /// Persist the queue and emit its current state to all windows
+    pub async fn emit_queue_changed<R: Runtime>(&self, app: &AppHandle<R>) {
+        self.persist_queue().await;
+        let snapshot = self.queue_snapshot().await;
+        crate::tray::update_tray_status(app, &snapshot);
+        let _ = app.emit(QUEUE_CHANGED_EVENT, snapshot);
+    }
+
+    /// Write all unfinished jobs to storage
+    async fn persist_queue(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+
+        let items: Vec<QueueItem> = {
+            let jobs = self.jobs.lock().await;
+            let queue = self.queue.lock().await;
+            ordered_jobs(&jobs, &queue)
+                .into_iter()
+                .map(|job| QueueItem {
+                    id: job.id.clone(),
+                    file_path: job.file_path.clone(),
+                    file_name: get_file_name(&job.file_path),
+                    status: match job.state {
+                        JobState::Queued => QueueItemStatus::Pending,
+                        JobState::Running | JobState::Paused => QueueItemStatus::Processing,
+                        JobState::Interrupted => QueueItemStatus::Interrupted,
+                        JobState::Failed => QueueItemStatus::Error,
+                    },
+                    progress: job.progress as f64,
+                    added_at: job.added_at.clone(),
+                    model_size: job.model_size,
+                    start_offset: job.start_offset,
+                    custom_model: job.custom_model.clone(),
+                    options: job.options.clone(),
+                    priority: job.priority,
+                })
+                .collect()
+        };
+
+        if let Err(e) = storage.save_queue(&items) {
+            eprintln!("Failed to persist job queue: {}", e);
+        }
+    }
+
+    /// Restore unfinished jobs persisted by a previous session
+    ///
+    /// Pending items are queued again in their original order. Items that were
+    /// still processing are marked as interrupted so they can be retried, and
+    /// failed items keep their partial segments. Returns the number of restored jobs.
+    pub async fn restore_queue(&self) -> Result<usize, AppError> {
+        let Some(storage) = &self.storage else {
+            return Ok(0);
+        };
+
+        let items = storage.load_queue()?;
+        let mut jobs = self.jobs.lock().await;
+        let mut queue = self.queue.lock().await;
+
+        let mut restored = 0;
+        for item in items {
+            let state = match item.status {
+                QueueItemStatus::Pending => JobState::Queued,
+                QueueItemStatus::Processing | QueueItemStatus::Interrupted => JobState::Interrupted,
+                QueueItemStatus::Error => JobState::Failed,
+                QueueItemStatus::Completed => continue,
+            };
+
+            if state == JobState::Queued {
+                queue.push_back(item.id.clone());
+            }
+            let segments = if state.is_retryable() {
+                storage.load_partial_segments(&item.id).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            jobs.insert(item.id.clone(), TranscriptionJob {
+                state,
+                priority: item.priority,
+                added_at: item.added_at,
+                progress: item.progress as u32,
+                segments,
+                ..TranscriptionJob::new(
+                    item.id,
+                    item.file_path,
+                    item.model_size,
+                    item.custom_model,
+                    item.start_offset,
+                    item.options,
+                )
+            });
+            restored += 1;
+        }
+
+        Ok(restored)
+    }
+}
+
+impl Default for SidecarManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Queue a job behind every queued job of the same or a higher priority
+fn insert_by_priority(queue: &mut VecDeque<String>, jobs: &HashMap<String, TranscriptionJob>, job_id: String) {
+    let priority = jobs.get(&job_id).map(|job| job.priority).unwrap_or_default();
+    let position = queue
+        .iter()
+        .position(|id| jobs.get(id).is_some_and(|job| job.priority < priority))
+        .unwrap_or(queue.len());
+    queue.insert(position, job_id);
+}
+
+/// List jobs with a sidecar (oldest first), then queued in dispatch order, then retryable
+fn ordered_jobs<'a>(
+    jobs: &'a HashMap<String, TranscriptionJob>,
+    queue: &VecDeque<String>,
+) -> Vec<&'a TranscriptionJob> {
+    let by_age = |include: fn(JobState) -> bool| {
+        let mut matching: Vec<&TranscriptionJob> = jobs.values().filter(|j| include(j.state)).collect();
+        matching.sort_by(|a, b| a.added_at.cmp(&b.added_at));
+        matching
+    };
+
+    let mut ordered = by_age(JobState::holds_slot);
+    ordered.extend(queue.iter().filter_map(|id| jobs.get(id)));
+    ordered.extend(by_age(JobState::is_retryable));
+    ordered
+}
+
+// ============================================
+// Process Suspension
+// ============================================
+
+/// Collect `root` and all of its descendants from a list of `(pid, parent_pid)` pairs
+///
+/// The packaged whisper-engine runs as a bootloader with a child interpreter
+/// process, so suspending only the direct child would not pause the work.
+fn collect_process_tree(root: u32, processes: &[(u32, u32)]) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        for &(pid, ppid) in processes {
+            if ppid == parent && pid != parent && !tree.contains(&pid) {
+                tree.push(pid);
+            }
+        }
+        i += 1;
+    }
+    tree
+}
+
+/// List `(pid, parent_pid)` pairs of all processes
+#[cfg(target_os = "linux")]
+fn list_processes() -> Result<Vec<(u32, u32)>, AppError> {
+    let entries = std::fs::read_dir("/proc")
+        .map_err(|e| AppError::SidecarError(format!("Failed to list processes: {}", e)))?;
+
+    Ok(entries
+        .filter_map(|entry| {
+            let pid: u32 = entry.ok()?.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+            // Format: pid (comm) state ppid ... where comm may contain spaces
+            let after_comm = &stat[stat.rfind(')')? + 1..];
+            let ppid = after_comm.split_whitespace().nth(1)?.parse().ok()?;
+            Some((pid, ppid))
+        })
+        .collect())
+}
+
+/// List `(pid, parent_pid)` pairs of all processes
+///
+/// Other Unix systems have no `/proc` to walk, so the process table is read from `ps`.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn list_processes() -> Result<Vec<(u32, u32)>, AppError> {
+    let output = std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid="])
+        .output()
+        .map_err(|e| AppError::SidecarError(format!("Failed to list processes: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::SidecarError(format!(
+            "Failed to list processes: ps exited with {}",
+            output.status
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let ppid = fields.next()?.parse().ok()?;
+            Some((pid, ppid))
+        })
+        .collect())
+}
+
+/// Send a signal to each process of a tree, returning the processes it failed for
+///
+/// Descendants that exited since the tree was listed are not failures.
+#[cfg(unix)]
+fn signal_processes(tree: &[u32], signal: libc::c_int) -> Vec<(u32, std::io::Error)> {
+    tree.iter()
+        .enumerate()
+        .filter_map(|(i, &target)| {
+            // SAFETY: kill has no memory-safety preconditions
+            if unsafe { libc::kill(target as libc::pid_t, signal) } == 0 {
+                return None;
+            }
+            let error = std::io::Error::last_os_error();
+            let exited = i > 0 && error.raw_os_error() == Some(libc::ESRCH);
+            (!exited).then_some((target, error))
+        })
+        .collect()
+}
+
+/// Suspend or continue a process and its descendants
+///
+/// Fails if any process of the tree could not be signalled. A failed suspend
+/// continues the processes it already stopped, so a tree is never left half paused.
+#[cfg(unix)]
+fn set_process_tree_suspended(pid: u32, suspend: bool) -> Result<(), AppError> {
+    let signal = if suspend { libc::SIGSTOP } else { libc::SIGCONT };
+    let tree = collect_process_tree(pid, &list_processes()?);
+
+    let failures = signal_processes(&tree, signal);
+    if failures.is_empty() {
+        return Ok(());
+    }
+    if suspend {
+        signal_processes(&tree, libc::SIGCONT);
+    }
+
+    let details: Vec<String> = failures
+        .iter()
+        .map(|(target, error)| format!("process {}: {}", target, error))
+        .collect();
+    Err(AppError::SidecarError(format!(
+        "Failed to {} {}",
+        if suspend { "suspend" } else { "resume" },
+        details.join(", ")
+    )))
+}
+
+/// Suspend or continue a process and its descendants
+#[cfg(windows)]
+fn set_process_tree_suspended(pid: u32, suspend: bool) -> Result<(), AppError> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, Thread32First, Thread32Next,
+        PROCESSENTRY32W, TH32CS_SNAPPROCESS, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenThread, ResumeThread, SuspendThread, THREAD_SUSPEND_RESUME,
+    };
+
+    // SAFETY: the snapshot handle is checked before use and closed on every path;
+    // entry structs are zero-initialised plain data with dwSize set as required.
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS | TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(AppError::SidecarError(format!(
+                "Failed to list processes: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+
+        let mut processes = Vec::new();
+        let mut process: PROCESSENTRY32W = std::mem::zeroed();
+        process.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        if Process32FirstW(snapshot, &mut process) != 0 {
+            loop {
+                processes.push((process.th32ProcessID, process.th32ParentProcessID));
+                if Process32NextW(snapshot, &mut process) == 0 {
+                    break;
+                }
+            }
+        }
+        let tree = collect_process_tree(pid, &processes);
+
+        let mut thread: THREADENTRY32 = std::mem::zeroed();
+        thread.dwSize = std::mem::size_of::<THREADENTRY32>() as u32;
+        if Thread32First(snapshot, &mut thread) != 0 {
+            loop {
+                if tree.contains(&thread.th32OwnerProcessID) {
+                    let handle = OpenThread(THREAD_SUSPEND_RESUME, 0, thread.th32ThreadID);
+                    if !handle.is_null() {
+                        if suspend {
+                            SuspendThread(handle);
+                        } else {
+                            ResumeThread(handle);
+                        }
+                        CloseHandle(handle);
+                    }
+                }
+                if Thread32Next(snapshot, &mut thread) == 0 {
+                    break;
+                }
+            }
+        }
+
+        CloseHandle(snapshot);
+    }
+    Ok(())
+}
+
+// ============================================
+// Output Handling
+// ============================================
+
+/// Number of malformed protocol messages after which a job is failed
+const MAX_PROTOCOL_VIOLATIONS: u32 = 5;
+
+/// Watchdog window for a job: the base timeout plus allowances for long media
+/// and for decoding one chunk of audio at the slowest expected speed
+fn watchdog_window(base: Duration, media_duration: f64) -> Duration {
+    let media_allowance = if media_duration.is_finite() && media_duration > 0.0 {
+        Duration::from_secs_f64(media_duration * WATCHDOG_SECONDS_PER_MEDIA_SECOND)
+    } else {
+        Duration::ZERO
+    };
+    base + media_allowance + Duration::from_secs_f64(ENGINE_CHUNK_SECONDS / SLOWEST_REAL_TIME_FACTOR)
+}
+
+/// How a worker's current job ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobEnd {
+    Completed,
+    Failed,
+}
+
+/// Get the checkpoint file of the custom model configured in settings
+///
+/// The setting is either a checkpoint path or the name of a checkpoint
+/// imported into the model cache. The engine loads any checkpoint file but
+/// only its built-in model names, which have model sizes of their own, so
+/// anything else is rejected before a worker is started.
+fn resolve_custom_model(custom_model: Option<&str>, models: Option<&ModelManager>) -> Result<String, AppError> {
+    let model = custom_model.map(str::trim).unwrap_or_default();
+    if model.is_empty() {
+        return Err(AppError::InvalidInput("No custom model is configured in settings".to_string()));
+    }
+    if std::path::Path::new(model).is_file() {
+        return Ok(model.to_string());
+    }
+    models
+        .and_then(|models| models.custom_model_path(model))
+        .map(|path| path.to_string_lossy().into_owned())
+        .ok_or_else(|| AppError::ModelNotFound(model.to_string()))
+}
+
+/// Whether a custom model refers to a checkpoint file rather than a model name
+fn is_model_path(model: &str) -> bool {
+    model.contains('/') || model.contains('\\') || model.ends_with(".pt")
+}
+
+/// Pick the device and CPU thread count of a job that is being started
+///
+/// The CPU budget is split evenly between the concurrency slots, so parallel
+/// jobs don't oversubscribe the cores. An explicit thread count from the job
+/// or settings is capped at that share.
+fn compute_allocation(
+    settings: &Settings,
+    options: &TranscriptionOptions,
+    max_concurrent: usize,
+) -> (Option<ComputeDevice>, usize) {
+    let budget = settings
+        .cpu_budget
+        .filter(|&budget| budget > 0)
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let share = (budget / max_concurrent.max(1)).max(1);
+
+    let threads = options
+        .threads
+        .or(settings.cpu_threads)
+        .filter(|&threads| threads > 0)
+        .map_or(share, |threads| threads.min(share));
+
+    (options.device.or(settings.device), threads)
+}
+
+/// Check that an engine can run a job for this app
+fn check_engine_compatibility(info: &EngineInfo, model: &str, device: Option<ComputeDevice>) -> Result<(), AppError> {
+    if info.protocol_version != SIDECAR_PROTOCOL_VERSION {
+        return Err(AppError::SidecarError(format!(
+            "Engine {} speaks protocol version {}, but this app requires version {}",
+            info.engine_version, info.protocol_version, SIDECAR_PROTOCOL_VERSION
+        )));
+    }
+
+    // Checkpoint files are loaded as they are; only named models must be known
+    if !is_model_path(model) && !info.models.iter().any(|m| m == model) {
+        return Err(AppError::SidecarError(format!(
+            "Engine {} does not support the {} model",
+            info.engine_version, model
+        )));
+    }
+
+    if let Some(device) = device {
+        if !info.devices.iter().any(|d| d == device.engine_name()) {
+            return Err(AppError::SidecarError(format!(
+                "Engine {} cannot run on the {} device",
+                info.engine_version,
+                device.engine_name()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether an unparseable line was meant as a protocol message
+///
+/// Anything else is noise printed by the engine or its libraries.
+fn is_protocol_line(line: &str) -> bool {
+    line.trim_start().starts_with('{')
+}
+
+/// Tolerance in seconds when matching resumed segments against kept ones
+const RESUME_OVERLAP_TOLERANCE: f64 = 0.05;
+
+/// Append a segment to a job's transcript, keeping segment IDs continuous
+///
+/// A resumed sidecar numbers its segments from zero again, so every segment is
+/// renumbered by position. After a resume, segments ending before `resume_cutoff`
+/// are duplicates from an earlier attempt and are dropped.
+fn append_segment(
+    segments: &mut Vec<Segment>,
+    mut segment: Segment,
+    resume_cutoff: Option<f64>,
+) -> Option<&Segment> {
+    if resume_cutoff.is_some_and(|cutoff| segment.end <= cutoff + RESUME_OVERLAP_TOLERANCE) {
+        return None;
+    }
+
+    segment.id = format!("seg_{:04}", segments.len());
+    segments.push(segment);
+    segments.last()
+}
+
+impl SidecarManager {
+    /// Handle a line of output from a job's worker
+    ///
+    /// Returns how the job ended if the line was its `complete` or `error` message.
+    async fn handle_sidecar_output<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        job_id: &str,
+        output: SidecarOutput,
+    ) -> Result<Option<JobEnd>, AppError> {
+        let message = match output {
+            SidecarOutput::Message(message) => message,
+            SidecarOutput::Invalid { line, error } if is_protocol_line(&line) => {
+                self.record_protocol_violation(job_id, error).await;
+                return Ok(None);
+            }
+            SidecarOutput::Invalid { line, .. } => {
+                self.record_noise(job_id, &line).await;
+                return Ok(None);
+            }
+        };
+
+        if !self.check_handshake(job_id, &message).await {
+            return Ok(None);
+        }
+
+        match message {
+            // Already recorded by the handshake check
+            SidecarMessage::Hello(_) => {}
+            SidecarMessage::Progress { percent, status } => {
+                let estimate = self.record_progress(job_id, percent, &status).await;
+                app.emit("transcription_progress", TranscriptionProgressPayload {
+                    job_id: job_id.to_string(),
+                    percent,
+                    status,
+                    estimate: estimate.unwrap_or_default(),
+                }).map_err(|e| AppError::SidecarError(format!("Failed to emit progress: {}", e)))?;
+            }
+            SidecarMessage::Segment { data } => {
+                let Some(data) = self.record_segment(job_id, data).await else {
+                    return Ok(None);
+                };
+                app.emit("transcription_segment", TranscriptionSegmentPayload {
+                    job_id: job_id.to_string(),
+                    segment: data,
+                }).map_err(|e| AppError::SidecarError(format!("Failed to emit segment: {}", e)))?;
+            }
+            SidecarMessage::Complete { language, duration } => {
+                // Save before notifying, so the history already contains the transcript
+                let transcript_id = match self.save_completed_transcript(job_id, &language, duration).await {
+                    Ok(id) => id,
+                    Err(e) => {
+                        // Fail the job instead, keeping its segments for a retry
+                        if let Some(job) = self.jobs.lock().await.get_mut(job_id) {
+                            job.abort_reason = Some(e);
+                        }
+                        return Ok(Some(JobEnd::Failed));
+                    }
+                };
+                self.record_throughput(job_id, duration).await;
+                // The job is over either way, so a failed emit must not keep it running
+                if let Err(e) = app.emit("transcription_complete", TranscriptionCompletePayload {
+                    job_id: job_id.to_string(),
+                    language,
+                    duration,
+                    transcript_id,
+                }) {
+                    eprintln!("Failed to emit complete: {}", e);
+                }
+                return Ok(Some(JobEnd::Completed));
+            }
+            SidecarMessage::Error { message } => {
+                // Reported together with the job's stderr tail
+                if let Some(job) = self.jobs.lock().await.get_mut(job_id) {
+                    job.engine_error = Some(message);
+                }
+                return Ok(Some(JobEnd::Failed));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Make sure the sidecar opened with a compatible `hello` handshake
+    ///
+    /// An engine that skips the handshake or announces an unsupported
+    /// protocol is killed, and the reason is reported when it exits.
+    /// Warm workers have already shaken hands when a job is handed to them.
+    /// Returns whether the message should be handled; output of jobs that are
+    /// no longer tracked is dropped.
+    async fn check_handshake(&self, job_id: &str, message: &SidecarMessage) -> bool {
+        let mut jobs = self.jobs.lock().await;
+        let Some(job) = jobs.get_mut(job_id) else {
+            return false;
+        };
+        if job.abort_reason.is_some() {
+            return false;
+        }
+
+        let result = match (message, &job.engine) {
+            (SidecarMessage::Hello(info), None) => {
+                check_engine_compatibility(info, &job.model(), job.device).map(|()| info.clone())
+            }
+            // A repeated handshake changes nothing
+            (SidecarMessage::Hello(_), Some(_)) => return false,
+            (_, Some(_)) => return true,
+            (_, None) => Err(AppError::SidecarError(
+                "Engine did not send a handshake; it is too old for this version of ScriptGrab".to_string(),
+            )),
+        };
+
+        match result {
+            Ok(info) => {
+                job.engine = Some(info);
+                true
+            }
+            Err(e) => {
+                job.abort(e);
+                false
+            }
+        }
+    }
+
+    /// Keep non-protocol stdout output with the job's diagnostics
+    async fn record_noise(&self, job_id: &str, line: &str) {
+        if let Some(job) = self.jobs.lock().await.get_mut(job_id) {
+            job.stderr.push(&format!("[stdout] {}", line));
+        }
+    }
+
+    /// Count a malformed protocol message, killing the sidecar once there are too many
+    async fn record_protocol_violation(&self, job_id: &str, error: AppError) {
+        let mut jobs = self.jobs.lock().await;
+        let Some(job) = jobs.get_mut(job_id) else {
+            return;
+        };
+
+        job.stderr.push(&format!("[protocol] {}", error));
+        job.protocol_violations += 1;
+
+        if job.protocol_violations >= MAX_PROTOCOL_VIOLATIONS && job.abort_reason.is_none() {
+            let count = job.protocol_violations;
+            job.abort(AppError::SidecarError(format!(
+                "Engine sent {} malformed messages; the sidecar build may not match this app",
+                count
+            )));
+        }
+    }
+
+    /// Remember the latest progress reported for a job
+    ///
+    /// Returns the job's time estimate, or `None` if it is not running.
+    async fn record_progress(&self, job_id: &str, percent: u32, status: &str) -> Option<ProgressEstimate> {
+        let mut jobs = self.jobs.lock().await;
+        let job = jobs.get_mut(job_id)?;
+        job.progress = percent;
+        job.status = status.to_string();
+        job.last_activity = Some(Instant::now());
+
+        let throughput = self.throughput.lock().await;
+        job.estimate(&throughput)
+    }
+
+    /// Add the speed of a completed attempt to the averages of its model
+    ///
+    /// Statistics are saved right away, so they survive a crash before the
+    /// app quits.
+    async fn record_throughput(&self, job_id: &str, duration: f64) {
+        let (model, audio_seconds, wall_seconds) = {
+            let jobs = self.jobs.lock().await;
+            let Some(job) = jobs.get(job_id) else {
+                return;
+            };
+            let Some(wall_seconds) = job.running_seconds() else {
+                return;
+            };
+            (job.model(), duration - job.start_offset, wall_seconds)
+        };
+
+        let mut throughput = self.throughput.lock().await;
+        throughput.record(&model, audio_seconds, wall_seconds);
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.save_throughput(&throughput) {
+                eprintln!("Failed to save throughput statistics: {}", e);
+            }
+        }
+    }
+
+    /// Get the average speed of each model, for estimating jobs before they start
+    pub async fn throughput_stats(&self) -> ThroughputStats {
+        self.throughput.lock().await.clone()
+    }
+
+    /// Remember a segment received for a job
+    ///
+    /// Returns the segment as stored, or `None` if it was dropped because it
+    /// overlaps segments kept from an earlier attempt or the job is no longer tracked.
+    async fn record_segment(&self, job_id: &str, segment: Segment) -> Option<Segment> {
+        let mut jobs = self.jobs.lock().await;
+        let job = jobs.get_mut(job_id)?;
+        job.last_activity = Some(Instant::now());
+        append_segment(&mut job.segments, segment, job.resume_cutoff).cloned()
+    }
+
+    /// Write the collected segments of a finished job to history
+    ///
+    /// The transcript is stored under the job ID. The job keeps its segments
+    /// until it is finished, so they survive a failed save. Returns `None`
+    /// when no storage is configured or the job is no longer tracked.
+    async fn save_completed_transcript(
+        &self,
+        job_id: &str,
+        language: &str,
+        duration: f64,
+    ) -> Result<Option<String>, AppError> {
+        let Some(storage) = &self.storage else {
+            return Ok(None);
+        };
+
+        let transcript = {
+            let jobs = self.jobs.lock().await;
+            let Some(job) = jobs.get(job_id) else {
+                return Ok(None);
+            };
+
+            StoredTranscript {
+                id: job.id.clone(),
+                file_name: get_file_name(&job.file_path),
+                file_path: job.file_path.clone(),
+                created_at: current_timestamp(),
+                duration,
+                language: language.to_string(),
+                model_size: job.model_size,
+                custom_model: job.custom_model.clone(),
+                options: job.options.clone(),
+                segments: job.segments.clone(),
+            }
+        };
+
+        storage.save_transcript(&transcript)?;
+        Ok(Some(transcript.id))
+    }
+}
+
+// ============================================
+// Tests
+// ============================================
 
 #[cfg(test)]
 mod tests {
@@ -308,4 +1941,1139 @@ mod tests {
         let result = manager.cancel_transcription("nonexistent-job-id").await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_max_concurrent_is_at_least_one() {
+        let manager = SidecarManager::with_max_concurrent(0);
+        assert_eq!(manager.max_concurrent_jobs(), 1);
+
+        manager.set_max_concurrent_jobs(4);
+        assert_eq!(manager.max_concurrent_jobs(), 4);
+
+        manager.set_max_concurrent_jobs(0);
+        assert_eq!(manager.max_concurrent_jobs(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_queued_jobs_keep_insertion_order() {
+        let manager = SidecarManager::new();
+        let files = vec!["a.mp3".to_string(), "b.mp3".to_string(), "c.mp3".to_string()];
+        let job_ids = manager.push_jobs(files.clone(), ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+
+        assert_eq!(manager.active_job_count().await, 3);
+        assert_eq!(manager.running_job_count().await, 0);
+
+        let snapshot = manager.queue_snapshot().await;
+        assert_eq!(snapshot.queued, 3);
+        assert_eq!(snapshot.running, 0);
+        let snapshot_ids: Vec<String> = snapshot.jobs.iter().map(|j| j.job_id.clone()).collect();
+        assert_eq!(snapshot_ids, job_ids);
+        let snapshot_files: Vec<String> = snapshot.jobs.iter().map(|j| j.file_path.clone()).collect();
+        assert_eq!(snapshot_files, files);
+    }
+
+    #[tokio::test]
+    async fn test_restore_queue_marks_processing_as_interrupted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = StorageManager::new(temp_dir.path().to_path_buf());
+        let item = |id: &str, status: QueueItemStatus| QueueItem {
+            id: id.to_string(),
+            file_path: format!("/path/to/{}.mp3", id),
+            file_name: format!("{}.mp3", id),
+            status,
+            progress: 0.0,
+            added_at: current_timestamp(),
+            model_size: ModelSize::Medium,
+            start_offset: 0.0,
+            custom_model: None,
+            options: TranscriptionOptions::default(),
+            priority: JobPriority::Normal,
+        };
+        storage
+            .save_queue(&[
+                item("running", QueueItemStatus::Processing),
+                item("first", QueueItemStatus::Pending),
+                item("second", QueueItemStatus::Pending),
+                item("done", QueueItemStatus::Completed),
+            ])
+            .unwrap();
+
+        let manager = SidecarManager::new()
+            .with_storage(StorageManager::new(temp_dir.path().to_path_buf()));
+        assert_eq!(manager.restore_queue().await.unwrap(), 3);
+
+        // Interrupted jobs do not count as active until retried
+        assert_eq!(manager.active_job_count().await, 2);
+        assert!(!manager.is_job_active("running").await);
+
+        let snapshot = manager.queue_snapshot().await;
+        let order: Vec<(&str, JobState)> = snapshot
+            .jobs
+            .iter()
+            .map(|j| (j.job_id.as_str(), j.state))
+            .collect();
+        assert_eq!(order, vec![
+            ("first", JobState::Queued),
+            ("second", JobState::Queued),
+            ("running", JobState::Interrupted),
+        ]);
+        assert_eq!(snapshot.jobs[0].model_size, ModelSize::Medium);
+
+        // Retrying puts the interrupted job at the back of the queue
+        manager.retry_transcription("running").await.unwrap();
+        assert!(manager.is_job_active("running").await);
+        let snapshot = manager.queue_snapshot().await;
+        assert_eq!(snapshot.queued, 3);
+        assert_eq!(snapshot.jobs[2].job_id, "running");
+
+        // Only interrupted jobs can be retried
+        assert!(manager.retry_transcription("first").await.is_err());
+    }
+
+    async fn job_order(manager: &SidecarManager) -> Vec<String> {
+        manager.queue_snapshot().await.jobs.into_iter().map(|j| j.job_id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_priority_orders_queue() {
+        let manager = SidecarManager::new();
+        let files = vec!["a.mp3".to_string(), "b.mp3".to_string(), "c.mp3".to_string()];
+        let ids = manager.push_jobs(files, ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+
+        // A high priority job goes ahead, a low priority one to the back
+        manager.set_job_priority(&ids[2], JobPriority::High).await.unwrap();
+        manager.set_job_priority(&ids[0], JobPriority::Low).await.unwrap();
+        assert_eq!(job_order(&manager).await, vec![ids[2].clone(), ids[1].clone(), ids[0].clone()]);
+
+        // New jobs queue behind the jobs of their own priority
+        let d = manager.push_jobs(vec!["d.mp3".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+        assert_eq!(job_order(&manager).await, vec![ids[2].clone(), ids[1].clone(), d[0].clone(), ids[0].clone()]);
+        assert_eq!(manager.get_job(&ids[2]).await.unwrap().priority, JobPriority::High);
+    }
+
+    #[tokio::test]
+    async fn test_move_and_bump_queued_jobs() {
+        let manager = SidecarManager::new();
+        let files = vec!["a.mp3".to_string(), "b.mp3".to_string(), "c.mp3".to_string(), "d.mp3".to_string()];
+        let ids = manager.push_jobs(files, ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+
+        manager.move_job(&ids[0], 2).await.unwrap();
+        assert_eq!(job_order(&manager).await, vec![ids[1].clone(), ids[2].clone(), ids[0].clone(), ids[3].clone()]);
+        manager.move_job(&ids[1], 99).await.unwrap();
+        assert_eq!(job_order(&manager).await, vec![ids[2].clone(), ids[0].clone(), ids[3].clone(), ids[1].clone()]);
+        manager.bump_job(&ids[3]).await.unwrap();
+        assert_eq!(job_order(&manager).await, vec![ids[3].clone(), ids[2].clone(), ids[0].clone(), ids[1].clone()]);
+
+        // Running jobs stay where they are
+        manager.jobs.lock().await.get_mut(&ids[2]).unwrap().state = JobState::Running;
+        manager.queue.lock().await.retain(|id| id != &ids[2]);
+        let error = manager.bump_job(&ids[2]).await.unwrap_err();
+        assert!(matches!(error, AppError::InvalidInput(_)));
+        manager.set_job_priority(&ids[2], JobPriority::High).await.unwrap();
+        assert_eq!(manager.get_job(&ids[2]).await.unwrap().state, JobState::Running);
+        assert_eq!(job_order(&manager).await, vec![ids[2].clone(), ids[3].clone(), ids[0].clone(), ids[1].clone()]);
+
+        assert!(manager.move_job("missing", 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_persist_queue_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = SidecarManager::new()
+            .with_storage(StorageManager::new(temp_dir.path().to_path_buf()));
+        let job_ids = manager
+            .push_jobs(vec!["/a.mp3".to_string(), "/b.mp3".to_string()], ModelSize::Small, None, 0.0, TranscriptionOptions::default())
+            .await;
+        manager.persist_queue().await;
+
+        let items = StorageManager::new(temp_dir.path().to_path_buf()).load_queue().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, job_ids[0]);
+        assert_eq!(items[0].file_name, "a.mp3");
+        assert_eq!(items[0].status, QueueItemStatus::Pending);
+        assert_eq!(items[1].id, job_ids[1]);
+        assert_eq!(items[1].model_size, ModelSize::Small);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_protocol_violations_abort_job() {
+        let manager = SidecarManager::new();
+        let job_ids = manager.push_jobs(vec!["/in/a.mp4".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+        let job_id = &job_ids[0];
+
+        manager.record_noise(job_id, "Downloading model...").await;
+        for _ in 0..MAX_PROTOCOL_VIOLATIONS - 1 {
+            manager.record_protocol_violation(job_id, AppError::SidecarError("bad".to_string())).await;
+        }
+        {
+            let jobs = manager.jobs.lock().await;
+            let job = &jobs[job_id];
+            assert!(job.abort_reason.is_none());
+            assert_eq!(job.stderr.lines()[0], "[stdout] Downloading model...");
+        }
+
+        manager.record_protocol_violation(job_id, AppError::SidecarError("bad".to_string())).await;
+
+        // The kill shows up as a signal, but the protocol failure is what gets reported
+        let killed = ExitStatus { code: None, signal: Some(9) };
+        let payload = manager.diagnose_failure(job_id, Some(killed)).await.unwrap();
+        assert!(matches!(payload.error, AppError::SidecarError(ref m) if m.contains("malformed")));
+        assert_eq!(payload.stderr_tail.len(), 1 + MAX_PROTOCOL_VIOLATIONS as usize);
+    }
+
+    #[test]
+    fn test_watchdog_window_scales_with_media_duration() {
+        let base = Duration::from_secs(300);
+        let chunk = Duration::from_secs(300);
+        assert_eq!(watchdog_window(base, 0.0), base + chunk);
+        assert_eq!(watchdog_window(base, f64::NAN), base + chunk);
+        assert_eq!(watchdog_window(base, 3600.0), Duration::from_secs(960));
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_keeps_slow_jobs_that_send_output() {
+        let manager = SidecarManager::new().with_watchdog_timeout(60);
+        let job_ids = manager
+            .push_jobs(vec!["/in/long.mp4".to_string()], ModelSize::LargeV3, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let job_id = &job_ids[0];
+        let window = watchdog_window(Duration::from_secs(60), 7200.0);
+        {
+            let mut jobs = manager.jobs.lock().await;
+            let job = jobs.get_mut(job_id).unwrap();
+            job.state = JobState::Running;
+            // Running for far longer than its window
+            job.started_at = Some(Utc::now() - chrono::Duration::hours(3));
+            job.stall_window = window;
+            job.last_activity = Some(Instant::now());
+        }
+
+        // Every chunk the engine finishes reports progress and segments
+        for chunk in 0..5 {
+            let end = (chunk + 1) as f64 * ENGINE_CHUNK_SECONDS;
+            manager.record_progress(job_id, 20 + chunk, "Transcribing...").await;
+            manager.record_segment(job_id, segment("seg_0000", end - ENGINE_CHUNK_SECONDS, end)).await;
+            let almost_silent = Instant::now() + window - Duration::from_secs(1);
+            assert!(manager.abort_stalled_jobs(almost_silent).await.is_empty());
+        }
+        assert_eq!(manager.get_job(job_id).await.unwrap().segment_count, 5);
+
+        // Once it falls silent for longer than its window, it is killed
+        let silent = Instant::now() + window + Duration::from_secs(1);
+        assert_eq!(manager.abort_stalled_jobs(silent).await, vec![job_id.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_aborts_only_silent_running_jobs() {
+        let manager = SidecarManager::new().with_watchdog_timeout(60);
+        let job_ids = manager
+            .push_jobs(vec!["/in/a.mp4".to_string(), "/in/b.mp4".to_string(), "/in/c.mp4".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let now = Instant::now();
+        let started = now.checked_sub(Duration::from_secs(90)).unwrap();
+        {
+            let mut jobs = manager.jobs.lock().await;
+            for (job_id, state) in job_ids.iter().zip([JobState::Running, JobState::Running, JobState::Paused]) {
+                let job = jobs.get_mut(job_id).unwrap();
+                job.state = state;
+                job.stall_window = Duration::from_secs(60);
+                job.last_activity = Some(started);
+            }
+        }
+
+        assert!(manager.abort_stalled_jobs(started + Duration::from_secs(30)).await.is_empty());
+
+        // Progress on the second job keeps it alive
+        manager.record_progress(&job_ids[1], 40, "Transcribing...").await;
+        let aborted = manager.abort_stalled_jobs(now + Duration::from_secs(1)).await;
+        assert_eq!(aborted, vec![job_ids[0].clone()]);
+
+        let killed = ExitStatus { code: None, signal: Some(9) };
+        let payload = manager.diagnose_failure(&job_ids[0], Some(killed)).await.unwrap();
+        assert!(matches!(payload.error, AppError::Timeout(_)));
+
+        // An already aborted job is not reported twice
+        assert!(manager.abort_stalled_jobs(now + Duration::from_secs(2)).await.is_empty());
+    }
+
+    fn engine_info(protocol_version: u32) -> EngineInfo {
+        EngineInfo {
+            protocol_version,
+            engine_version: "0.1.0".to_string(),
+            models: vec!["base".to_string(), "small".to_string()],
+            devices: vec!["cpu".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_check_engine_compatibility() {
+        assert!(check_engine_compatibility(&engine_info(SIDECAR_PROTOCOL_VERSION), "small", None).is_ok());
+
+        let error = check_engine_compatibility(&engine_info(SIDECAR_PROTOCOL_VERSION + 1), "base", None).unwrap_err();
+        assert!(matches!(error, AppError::SidecarError(ref m) if m.contains("protocol version")));
+
+        let error = check_engine_compatibility(&engine_info(SIDECAR_PROTOCOL_VERSION), "medium", None).unwrap_err();
+        assert!(matches!(error, AppError::SidecarError(ref m) if m.contains("medium")));
+
+        // Checkpoint files do not need to be announced
+        assert!(check_engine_compatibility(&engine_info(SIDECAR_PROTOCOL_VERSION), "/models/tr-meetings.pt", None).is_ok());
+
+        // The engine must offer the requested device
+        let info = engine_info(SIDECAR_PROTOCOL_VERSION);
+        assert!(check_engine_compatibility(&info, "base", Some(ComputeDevice::Cpu)).is_ok());
+        let error = check_engine_compatibility(&info, "base", Some(ComputeDevice::Cuda)).unwrap_err();
+        assert!(matches!(error, AppError::SidecarError(ref m) if m.contains("cuda")));
+    }
+
+    #[test]
+    fn test_compute_allocation_splits_cpu_budget() {
+        let settings = Settings { cpu_budget: Some(8), ..Settings::default() };
+        let options = TranscriptionOptions::default();
+
+        assert_eq!(compute_allocation(&settings, &options, 1), (None, 8));
+        assert_eq!(compute_allocation(&settings, &options, 2), (None, 4));
+        assert_eq!(compute_allocation(&settings, &options, 3), (None, 2));
+        // Every job gets at least one thread
+        assert_eq!(compute_allocation(&settings, &options, 16), (None, 1));
+
+        // Explicit thread counts are capped at the job's share
+        let settings = Settings { cpu_threads: Some(3), ..settings };
+        assert_eq!(compute_allocation(&settings, &options, 1), (None, 3));
+        assert_eq!(compute_allocation(&settings, &options, 4), (None, 2));
+
+        // A job's own options win over settings
+        let settings = Settings { device: Some(ComputeDevice::Cuda), ..settings };
+        let options = TranscriptionOptions { device: Some(ComputeDevice::Cpu), threads: Some(1), ..options };
+        assert_eq!(compute_allocation(&settings, &options, 1), (Some(ComputeDevice::Cpu), 1));
+        let options = TranscriptionOptions { device: None, threads: None, ..options };
+        assert_eq!(compute_allocation(&settings, &options, 1), (Some(ComputeDevice::Cuda), 3));
+    }
+
+    #[test]
+    fn test_resolve_custom_model() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let checkpoint = temp_dir.path().join("whisper-tr.pt");
+        std::fs::write(&checkpoint, b"").unwrap();
+        let checkpoint = checkpoint.to_string_lossy().into_owned();
+
+        assert_eq!(resolve_custom_model(Some(&format!(" {} ", checkpoint)), None).unwrap(), checkpoint);
+        assert!(matches!(
+            resolve_custom_model(Some("large-v3-turbo"), None),
+            Err(AppError::ModelNotFound(ref m)) if m == "large-v3-turbo"
+        ));
+        assert!(matches!(resolve_custom_model(None, None), Err(AppError::InvalidInput(_))));
+        assert!(matches!(resolve_custom_model(Some("  "), None), Err(AppError::InvalidInput(_))));
+        assert!(matches!(
+            resolve_custom_model(Some("/missing/model.pt"), None),
+            Err(AppError::ModelNotFound(ref m)) if m == "/missing/model.pt"
+        ));
+
+        // Checkpoints imported into the model cache are found by name
+        let models = ModelManager::new(temp_dir.path().join("whisper"));
+        let custom_dir = temp_dir.path().join("whisper").join("custom");
+        std::fs::create_dir_all(&custom_dir).unwrap();
+        std::fs::write(custom_dir.join("whisper-tr-meetings.pt"), b"").unwrap();
+        let imported = custom_dir.join("whisper-tr-meetings.pt").to_string_lossy().into_owned();
+        assert_eq!(resolve_custom_model(Some("whisper-tr-meetings"), Some(&models)).unwrap(), imported);
+        assert_eq!(resolve_custom_model(Some("whisper-tr-meetings.pt"), Some(&models)).unwrap(), imported);
+        assert!(matches!(
+            resolve_custom_model(Some("large-v3-turbo"), Some(&models)),
+            Err(AppError::ModelNotFound(ref m)) if m == "large-v3-turbo"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_must_come_first() {
+        let manager = SidecarManager::new();
+        let job_ids = manager
+            .push_jobs(vec!["/in/a.mp4".to_string(), "/in/b.mp4".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let progress = SidecarMessage::Progress { percent: 5, status: "Loading".to_string() };
+        let hello = SidecarMessage::Hello(engine_info(SIDECAR_PROTOCOL_VERSION));
+
+        // A compatible engine is accepted and may then report progress
+        assert!(manager.check_handshake(&job_ids[0], &hello).await);
+        assert!(manager.check_handshake(&job_ids[0], &progress).await);
+        assert!(!manager.check_handshake(&job_ids[0], &hello).await);
+        assert!(manager.jobs.lock().await[&job_ids[0]].abort_reason.is_none());
+
+        // An engine that starts working without a handshake is rejected
+        assert!(!manager.check_handshake(&job_ids[1], &progress).await);
+        assert!(!manager.check_handshake(&job_ids[1], &hello).await);
+        let payload = manager.diagnose_failure(&job_ids[1], None).await.unwrap();
+        assert!(matches!(payload.error, AppError::SidecarError(ref m) if m.contains("handshake")));
+
+        // Output of a cancelled job is dropped
+        assert!(!manager.check_handshake("job_missing", &progress).await);
+        assert!(manager.record_segment("job_missing", segment("seg_0000", 0.0, 1.0)).await.is_none());
+    }
+
+    #[test]
+    fn test_is_protocol_line() {
+        assert!(is_protocol_line("  {\"type\": \"progr"));
+        assert!(!is_protocol_line("100%|██████████| 139M/139M"));
+    }
+
+    #[tokio::test]
+    async fn test_completed_transcript_is_saved_to_history() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = SidecarManager::new()
+            .with_storage(StorageManager::new(temp_dir.path().to_path_buf()));
+        let job_ids = manager
+            .push_jobs(vec!["/path/to/meeting.m4a".to_string()], ModelSize::Medium, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let job_id = &job_ids[0];
+
+        for (i, text) in ["Merhaba", "dünya"].iter().enumerate() {
+            manager.record_segment(job_id, Segment {
+                id: format!("seg_{:04}", i),
+                start: i as f64,
+                end: i as f64 + 1.0,
+                text: text.to_string(),
+                words: Vec::new(),
+            }).await;
+        }
+
+        let saved = manager.save_completed_transcript(job_id, "tr", 2.0).await.unwrap();
+        assert_eq!(saved.as_deref(), Some(job_id.as_str()));
+
+        let storage = StorageManager::new(temp_dir.path().to_path_buf());
+        let transcript = storage.load_transcript(job_id).unwrap();
+        assert_eq!(transcript.file_name, "meeting.m4a");
+        assert_eq!(transcript.file_path, "/path/to/meeting.m4a");
+        assert_eq!(transcript.model_size, ModelSize::Medium);
+        assert_eq!(transcript.language, "tr");
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[1].text, "dünya");
+        assert_eq!(storage.get_history().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_collect_process_tree() {
+        // 10 -> 11 -> 13, 10 -> 12, unrelated 20 -> 21
+        let processes = [(11, 10), (12, 10), (13, 11), (21, 20), (10, 1)];
+        let mut tree = collect_process_tree(10, &processes);
+        tree.sort();
+        assert_eq!(tree, vec![10, 11, 12, 13]);
+
+        assert_eq!(collect_process_tree(42, &processes), vec![42]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_suspend_and_resume_process_tree() {
+        let process_state = |pid: u32| {
+            let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap();
+            let after_comm = &stat[stat.rfind(')').unwrap() + 1..];
+            after_comm.split_whitespace().next().unwrap().to_string()
+        };
+        let wait_for_state = |pid: u32, expected: &str| {
+            for _ in 0..100 {
+                if process_state(pid) == expected {
+                    return true;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            false
+        };
+
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+
+        set_process_tree_suspended(pid, true).unwrap();
+        assert!(wait_for_state(pid, "T"), "process should be stopped");
+
+        set_process_tree_suspended(pid, false).unwrap();
+        assert!(wait_for_state(pid, "S"), "process should be sleeping again");
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_suspend_exited_process_fails() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        assert!(set_process_tree_suspended(pid, true).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pause_requires_running_job() {
+        let manager = SidecarManager::new();
+        let job_ids = manager.push_jobs(vec!["a.mp3".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+
+        // Queued jobs have no process to suspend
+        assert!(manager.pause_transcription(&job_ids[0]).await.is_err());
+        assert!(manager.resume_transcription(&job_ids[0]).await.is_err());
+        assert!(manager.pause_transcription("nonexistent-job-id").await.is_err());
+    }
+
+    #[test]
+    fn test_paused_jobs_hold_a_slot() {
+        assert!(JobState::Running.holds_slot());
+        assert!(JobState::Paused.holds_slot());
+        assert!(!JobState::Queued.holds_slot());
+        assert!(!JobState::Interrupted.holds_slot());
+    }
+
+    fn segment(id: &str, start: f64, end: f64) -> Segment {
+        Segment {
+            id: id.to_string(),
+            start,
+            end,
+            text: format!("{}-{}", start, end),
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_segment_renumbers_and_drops_overlap() {
+        let mut segments = vec![segment("seg_0000", 0.0, 4.0), segment("seg_0001", 4.0, 9.5)];
+
+        // A resumed sidecar restarts its numbering and may repeat the boundary segment
+        let cutoff = Some(9.5);
+        assert!(append_segment(&mut segments, segment("seg_0000", 4.0, 9.5), cutoff).is_none());
+        let appended = append_segment(&mut segments, segment("seg_0001", 9.5, 12.0), cutoff).unwrap();
+        assert_eq!(appended.id, "seg_0002");
+        append_segment(&mut segments, segment("seg_0002", 12.0, 15.0), cutoff);
+
+        let ids: Vec<&str> = segments.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["seg_0000", "seg_0001", "seg_0002", "seg_0003"]);
+    }
+
+    #[test]
+    fn test_append_segment_keeps_short_segments_without_resume() {
+        let mut segments = vec![segment("seg_0000", 0.0, 4.0)];
+
+        // Zero-length and short segments of a first attempt are not duplicates
+        assert!(append_segment(&mut segments, segment("seg_0001", 4.0, 4.0), None).is_some());
+        assert!(append_segment(&mut segments, segment("seg_0002", 4.0, 4.03), None).is_some());
+
+        let ids: Vec<&str> = segments.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["seg_0000", "seg_0001", "seg_0002"]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_resumes_after_last_segment() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = SidecarManager::new()
+            .with_storage(StorageManager::new(temp_dir.path().to_path_buf()));
+        let options = TranscriptionOptions {
+            language: Some("tr".to_string()),
+            initial_prompt: Some("Toplantı notları".to_string()),
+            ..Default::default()
+        };
+        let job_ids = manager
+            .push_jobs(vec!["/long.mp3".to_string()], ModelSize::Base, None, 0.0, options.clone())
+            .await;
+        let job_id = &job_ids[0];
+
+        // Simulate the dispatcher and a sidecar that fails after two segments
+        manager.queue.lock().await.clear();
+        manager.jobs.lock().await.get_mut(job_id).unwrap().state = JobState::Running;
+        manager.record_segment(job_id, segment("seg_0000", 0.0, 30.0)).await;
+        manager.record_segment(job_id, segment("seg_0001", 30.0, 61.25)).await;
+        manager.fail_job(job_id).await;
+
+        assert!(!manager.is_job_active(job_id).await);
+        assert_eq!(manager.queue_snapshot().await.failed, 1);
+
+        // Partial segments survive a restart
+        manager.persist_queue().await;
+        let restored = SidecarManager::new()
+            .with_storage(StorageManager::new(temp_dir.path().to_path_buf()));
+        assert_eq!(restored.restore_queue().await.unwrap(), 1);
+
+        restored.retry_transcription(job_id).await.unwrap();
+        {
+            let jobs = restored.jobs.lock().await;
+            let job = jobs.get(job_id).unwrap();
+            assert_eq!(job.state, JobState::Queued);
+            assert_eq!(job.start_offset, 61.25);
+            assert_eq!(job.resume_cutoff, Some(61.25));
+            assert_eq!(job.segments.len(), 2);
+            assert_eq!(
+                job.engine_request(),
+                EngineRequest::Transcribe {
+                    job_id: job_id.clone(),
+                    audio_path: "/long.mp3".to_string(),
+                    model: "base".to_string(),
+                    start_offset: 61.25,
+                    language: Some("tr".to_string()),
+                    task: TranscriptionTask::Transcribe,
+                    initial_prompt: Some("Toplantı notları".to_string()),
+                    temperature: 0.0,
+                    device: None,
+                    threads: None,
+                }
+            );
+        }
+
+        // The resumed sidecar numbers from zero and may repeat the boundary segment;
+        // the merged transcript stays continuous
+        assert!(restored.record_segment(job_id, segment("seg_0000", 30.0, 61.25)).await.is_none());
+        restored.record_segment(job_id, segment("seg_0000", 61.25, 70.0)).await;
+        let saved = restored.save_completed_transcript(job_id, "en", 70.0).await.unwrap();
+        assert!(saved.is_some());
+
+        let transcript = StorageManager::new(temp_dir.path().to_path_buf())
+            .load_transcript(job_id)
+            .unwrap();
+        let ids: Vec<&str> = transcript.segments.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["seg_0000", "seg_0001", "seg_0002"]);
+        assert_eq!(transcript.segments[2].start, 61.25);
+        assert_eq!(transcript.options, options);
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_jobs() {
+        let manager = SidecarManager::new();
+        let job_ids = manager
+            .push_jobs(vec!["/in/first.mp3".to_string(), "/in/second.wav".to_string()], ModelSize::Small, None, 0.0, TranscriptionOptions::default())
+            .await;
+
+        // Simulate the first job running with some output
+        {
+            let mut jobs = manager.jobs.lock().await;
+            let job = jobs.get_mut(&job_ids[0]).unwrap();
+            job.state = JobState::Running;
+            job.started_at = Some(Utc::now() - chrono::Duration::seconds(5));
+        }
+        manager.queue.lock().await.retain(|id| id != &job_ids[0]);
+        manager.record_progress(&job_ids[0], 35, "Transcribing... 35%").await;
+        manager.record_segment(&job_ids[0], segment("seg_0000", 0.0, 2.0)).await;
+
+        let jobs = manager.list_jobs().await;
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].job_id, job_ids[0]);
+        assert_eq!(jobs[0].state, JobState::Running);
+        assert_eq!(jobs[0].percent, 35);
+        assert_eq!(jobs[0].status, "Transcribing... 35%");
+        assert_eq!(jobs[0].segment_count, 1);
+        assert_eq!(jobs[0].model_size, ModelSize::Small);
+        assert!(jobs[0].elapsed_seconds.unwrap() >= 5.0);
+
+        let queued = manager.get_job(&job_ids[1]).await.unwrap();
+        assert_eq!(queued.file_name, "second.wav");
+        assert_eq!(queued.state, JobState::Queued);
+        assert!(queued.started_at.is_none());
+        assert!(queued.elapsed_seconds.is_none());
+
+        assert!(manager.get_job("nonexistent-job-id").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_failure_uses_engine_error_and_stderr() {
+        let manager = SidecarManager::new();
+        let job_ids = manager.push_jobs(vec!["/in/a.mp4".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default()).await;
+        let job_id = &job_ids[0];
+        let clean = ExitStatus { code: Some(0), signal: None };
+        let failed = ExitStatus { code: Some(1), signal: None };
+
+        // A worker must not exit in the middle of a job, even cleanly
+        let payload = manager.diagnose_failure(job_id, Some(clean)).await.unwrap();
+        assert!(matches!(payload.error, AppError::TranscriptionFailed(ref m) if m.contains("before")));
+
+        {
+            let mut jobs = manager.jobs.lock().await;
+            let job = jobs.get_mut(job_id).unwrap();
+            job.stderr.push("[mov,mp4,m4a] moov atom not found\n");
+            job.engine_error = Some("Failed to load audio".to_string());
+        }
+
+        // A worker that reports an error lives on; the job is classified without an exit status
+        let payload = manager.diagnose_failure(job_id, None).await.unwrap();
+        assert!(matches!(payload.error, AppError::DecodeFailed(_)));
+
+        let payload = manager.diagnose_failure(job_id, Some(failed)).await.unwrap();
+        assert!(matches!(payload.error, AppError::DecodeFailed(_)));
+        assert_eq!(payload.message, payload.error.to_string());
+        assert_eq!(payload.stderr_tail, vec!["[mov,mp4,m4a] moov atom not found"]);
+
+        // Cancelled jobs are no longer tracked and report nothing
+        manager.cancel_transcription(job_id).await.unwrap();
+        assert!(manager.diagnose_failure(job_id, Some(failed)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_running_job_reports_time_estimate() {
+        let manager = SidecarManager::new();
+        manager.throughput.lock().await.record("base", 100.0, 50.0);
+        let job_ids = manager
+            .push_jobs(vec!["/in/a.mp3".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let job_id = &job_ids[0];
+
+        // Queued jobs have no estimate of their own
+        assert_eq!(manager.get_job(job_id).await.unwrap().eta_seconds, None);
+
+        {
+            let mut jobs = manager.jobs.lock().await;
+            let job = jobs.get_mut(job_id).unwrap();
+            job.state = JobState::Running;
+            job.started_at = Some(Utc::now() - chrono::Duration::seconds(60));
+            job.media_duration = Some(600.0);
+        }
+
+        // Before the first segment, the model average of 2x real time applies
+        let estimate = manager.record_progress(job_id, 20, "Transcribing...").await.unwrap();
+        assert!((estimate.elapsed_seconds - 60.0).abs() < 1.0);
+        assert_eq!(estimate.real_time_factor, None);
+        assert!((estimate.eta_seconds.unwrap() - 240.0).abs() < 1.0);
+
+        // Afterwards the job's own speed counts
+        manager.record_segment(job_id, segment("seg_0000", 0.0, 240.0)).await;
+        let info = manager.get_job(job_id).await.unwrap();
+        assert!((info.real_time_factor.unwrap() - 4.0).abs() < 0.1);
+        assert!((info.eta_seconds.unwrap() - 90.0).abs() < 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_follows_segments_as_they_stream_in() {
+        let manager = SidecarManager::new();
+        manager.throughput.lock().await.record("base", 100.0, 50.0);
+        let job_ids = manager
+            .push_jobs(vec!["/in/a.mp3".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let job_id = &job_ids[0];
+        async fn started(manager: &SidecarManager, job_id: &str, seconds_ago: i64) {
+            let mut jobs = manager.jobs.lock().await;
+            let job = jobs.get_mut(job_id).unwrap();
+            job.state = JobState::Running;
+            job.started_at = Some(Utc::now() - chrono::Duration::seconds(seconds_ago));
+            job.media_duration = Some(600.0);
+        }
+
+        // While the first chunk is decoded, only the model average is known
+        started(&manager, job_id, 30).await;
+        let estimate = manager.record_progress(job_id, 20, "Model loaded").await.unwrap();
+        assert_eq!(estimate.real_time_factor, None);
+        assert!((estimate.eta_seconds.unwrap() - 270.0).abs() < 1.0);
+
+        // Each chunk's segments update the job's own speed
+        manager.record_segment(job_id, segment("seg_0000", 0.0, 30.0)).await;
+        manager.record_segment(job_id, segment("seg_0001", 30.0, 60.0)).await;
+        let estimate = manager.record_progress(job_id, 27, "Transcribing...").await.unwrap();
+        assert!((estimate.real_time_factor.unwrap() - 2.0).abs() < 0.1);
+        assert!((estimate.eta_seconds.unwrap() - 270.0).abs() < 5.0);
+
+        // A slower second chunk lowers the speed and pushes the estimate out
+        started(&manager, job_id, 60).await;
+        manager.record_segment(job_id, segment("seg_0000", 60.0, 90.0)).await;
+        let estimate = manager.record_progress(job_id, 31, "Transcribing...").await.unwrap();
+        assert!((estimate.real_time_factor.unwrap() - 1.5).abs() < 0.1);
+        assert!((estimate.eta_seconds.unwrap() - 340.0).abs() < 5.0);
+
+        // Time without new segments counts against the speed as well
+        started(&manager, job_id, 90).await;
+        let info = manager.get_job(job_id).await.unwrap();
+        assert!((info.real_time_factor.unwrap() - 1.0).abs() < 0.1);
+        assert!((info.eta_seconds.unwrap() - 510.0).abs() < 5.0);
+        assert_eq!(info.segment_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_excludes_time_paused() {
+        let manager = SidecarManager::new();
+        let job_ids = manager
+            .push_jobs(vec!["/in/a.mp3".to_string()], ModelSize::Base, None, 0.0, TranscriptionOptions::default())
+            .await;
+        let job_id = &job_ids[0];
+
+        {
+            let mut jobs = manager.jobs.lock().await;
+            let job = jobs.get_mut(job_id).unwrap();
+            job.state = JobState::Running;
+            job.started_at = Some(Utc::now() - chrono::Duration::seconds(60));
+            job.paused_for = Duration::from_secs(30);
+            job.media_duration = Some(600.0);
+        }
+        manager.record_segment(job_id, segment("seg_0000", 0.0, 60.0)).await;
+
+        // 60 seconds of audio in 30 seconds of work
+        let info = manager.get_job(job_id).await.unwrap();
+        assert!((info.elapsed_seconds.unwrap() - 30.0).abs() < 1.0);
+        assert!((info.real_time_factor.unwrap() - 2.0).abs() < 0.1);
+        assert!((info.eta_seconds.unwrap() - 270.0).abs() < 5.0);
+
+        // The throughput statistics only count time spent running as well
+        manager.record_throughput(job_id, 60.0).await;
+        let stats = manager.throughput_stats().await;
+        assert!((stats.real_time_factor("base").unwrap() - 2.0).abs() < 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_job_removes_it_from_queue() {
+        let manager = SidecarManager::new();
+        let job_ids = manager
+            .push_jobs(vec!["a.mp3".to_string(), "b.mp3".to_string()], ModelSize::Small, None, 0.0, TranscriptionOptions::default())
+            .await;
+
+        manager.cancel_transcription(&job_ids[0]).await.unwrap();
+
+        assert!(!manager.is_job_active(&job_ids[0]).await);
+        assert!(manager.is_job_active(&job_ids[1]).await);
+        let snapshot = manager.queue_snapshot().await;
+        assert_eq!(snapshot.jobs.len(), 1);
+        assert_eq!(snapshot.jobs[0].job_id, job_ids[1]);
+    }
+
+    // ============================================
+    // End-to-end tests on the mock backend
+    // ============================================
+
+    use crate::mock_backend::{MockBackend, MockStep};
+    use crate::models::TranscriptionTask;
+    use tauri::test::{mock_app, MockRuntime};
+    use tauri::{App, Listener};
+    use tokio::sync::mpsc;
+
+    const APP_EVENTS: [&str; 5] = [
+        "transcription_progress",
+        "transcription_segment",
+        "transcription_complete",
+        "transcription_error",
+        QUEUE_CHANGED_EVENT,
+    ];
+
+    /// A running manager on a mock app, with the events it emitted
+    struct Harness {
+        app: App<MockRuntime>,
+        manager: SidecarManager,
+        events: mpsc::UnboundedReceiver<(String, serde_json::Value)>,
+        media: std::path::PathBuf,
+        temp_dir: tempfile::TempDir,
+    }
+
+    impl Harness {
+        fn new(backend: &MockBackend) -> Self {
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let media = temp_dir.path().join("interview.wav");
+            std::fs::write(&media, b"").unwrap();
+
+            let app = mock_app();
+            let (tx, events) = mpsc::unbounded_channel();
+            for name in APP_EVENTS {
+                let tx = tx.clone();
+                app.listen_any(name, move |event| {
+                    let payload = serde_json::from_str(event.payload()).unwrap();
+                    let _ = tx.send((name.to_string(), payload));
+                });
+            }
+
+            let manager = SidecarManager::new()
+                .with_storage(StorageManager::new(temp_dir.path().join("data")))
+                .with_backend(Arc::new(backend.clone()));
+            manager.start_dispatcher(app.handle().clone());
+
+            Self { app, manager, events, media, temp_dir }
+        }
+
+        fn storage(&self) -> StorageManager {
+            StorageManager::new(self.temp_dir.path().join("data"))
+        }
+
+        async fn start(&self, model_size: ModelSize) -> String {
+            self.manager
+                .start_transcription(self.app.handle().clone(), self.media.to_string_lossy().into_owned(), model_size, 0.0, None)
+                .await
+                .unwrap()
+        }
+
+        /// Wait for the next event with the given name, skipping others
+        async fn expect(&mut self, name: &str) -> serde_json::Value {
+            let wait = async {
+                loop {
+                    match self.events.recv().await {
+                        Some((event, payload)) if event == name => return payload,
+                        Some(_) => continue,
+                        None => panic!("event listeners were dropped"),
+                    }
+                }
+            };
+            tokio::time::timeout(Duration::from_secs(10), wait)
+                .await
+                .unwrap_or_else(|_| panic!("timed out waiting for {}", name))
+        }
+
+        /// Wait for a queue snapshot that satisfies `done`
+        async fn expect_queue(&mut self, done: impl Fn(&serde_json::Value) -> bool) {
+            while !done(&self.expect(QUEUE_CHANGED_EVENT).await) {}
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_transcribes_and_saves_history() {
+        let mut script = vec![MockStep::Delay(Duration::from_millis(20))];
+        script.extend(MockStep::transcript(
+            "en",
+            9.0,
+            vec![segment("seg_0000", 0.0, 4.0), segment("seg_0001", 4.0, 9.0)],
+        ));
+        let backend = MockBackend::new(script);
+        let mut harness = Harness::new(&backend);
+
+        // Jobs started without options use the defaults from settings
+        let defaults = TranscriptionOptions {
+            language: Some("tr".to_string()),
+            task: TranscriptionTask::Translate,
+            ..Default::default()
+        };
+        harness
+            .storage()
+            .save_settings(&Settings { default_transcription_options: defaults.clone(), ..Default::default() })
+            .unwrap();
+
+        let job_id = harness.start(ModelSize::Small).await;
+
+        let progress = harness.expect("transcription_progress").await;
+        assert_eq!(progress["job_id"], job_id.as_str());
+        assert_eq!(progress["percent"], 20);
+        assert!(progress["elapsed_seconds"].as_f64().unwrap() >= 0.0);
+        let segment = harness.expect("transcription_segment").await;
+        assert_eq!(segment["segment"]["id"], "seg_0000");
+        let complete = harness.expect("transcription_complete").await;
+        assert_eq!(complete["language"], "en");
+        assert_eq!(complete["transcript_id"], job_id.as_str());
+        harness.expect_queue(|q| q["jobs"].as_array().unwrap().is_empty()).await;
+
+        let storage = harness.storage();
+        let transcript = storage.load_transcript(&job_id).unwrap();
+        assert_eq!(transcript.file_name, "interview.wav");
+        assert_eq!(transcript.model_size, ModelSize::Small);
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.options, defaults);
+        assert_eq!(storage.get_history().unwrap().len(), 1);
+        assert!(harness.manager.get_job(&job_id).await.is_err());
+
+        // The next job runs on the same warm worker
+        let second = harness.start(ModelSize::Small).await;
+        let complete = harness.expect("transcription_complete").await;
+        assert_eq!(complete["job_id"], second.as_str());
+        assert_eq!(backend.spawn_count(), 1);
+
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        let EngineRequest::Transcribe { job_id: requested, audio_path, model, language, task, .. } = &requests[0];
+        assert_eq!(requested, &job_id);
+        assert_eq!(audio_path, &harness.media.to_string_lossy());
+        assert_eq!(model, "small");
+        assert_eq!(language.as_deref(), Some("tr"));
+        assert_eq!(*task, TranscriptionTask::Translate);
+
+        // The completed jobs taught the manager how fast the model is
+        let stats = harness.manager.throughput_stats().await;
+        assert!(stats.real_time_factor("small").is_some());
+        assert_eq!(storage.load_throughput().unwrap(), stats);
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_cancel_stops_running_job() {
+        let backend = MockBackend::new(vec![
+            MockStep::Message(SidecarMessage::Progress { percent: 10, status: "Loading".to_string() }),
+            MockStep::Hang,
+        ]);
+        let mut harness = Harness::new(&backend);
+
+        let job_id = harness.start(ModelSize::Base).await;
+        harness.expect("transcription_progress").await;
+
+        harness.manager.cancel_transcription(&job_id).await.unwrap();
+        assert!(harness.manager.get_job(&job_id).await.is_err());
+
+        // The killed worker frees its slot without reporting an error
+        harness.expect_queue(|q| q["running"] == 0).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        while let Ok((event, payload)) = harness.events.try_recv() {
+            assert!(!event.starts_with("transcription_"), "unexpected {}: {}", event, payload);
+        }
+        assert!(harness.storage().get_history().unwrap().is_empty());
+
+        // A cancelled worker is not reused
+        harness.start(ModelSize::Base).await;
+        harness.expect("transcription_progress").await;
+        assert_eq!(backend.spawn_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_crash_fails_job_and_keeps_segments() {
+        let backend = MockBackend::new(vec![
+            MockStep::Message(SidecarMessage::Segment { data: segment("seg_0000", 0.0, 3.0) }),
+            MockStep::Stderr("RuntimeError: CUDA out of memory".to_string()),
+            MockStep::Crash(ExitStatus { code: Some(1), signal: None }),
+        ]);
+        let mut harness = Harness::new(&backend);
+
+        let job_id = harness.start(ModelSize::Medium).await;
+
+        let error = harness.expect("transcription_error").await;
+        assert_eq!(error["job_id"], job_id.as_str());
+        assert_eq!(error["error"]["type"], "OutOfMemory");
+        assert_eq!(error["stderr_tail"][0], "RuntimeError: CUDA out of memory");
+
+        assert_eq!(harness.manager.get_job(&job_id).await.unwrap().state, JobState::Failed);
+        let partial = harness.storage().load_partial_segments(&job_id).unwrap();
+        assert_eq!(partial.len(), 1);
+        assert!(harness.storage().get_history().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_resumes_streamed_job_after_crash() {
+        // The engine sends each chunk's segments as soon as it is decoded
+        let chunk = |percent: u32, start: f64, end: f64| {
+            vec![
+                MockStep::Message(SidecarMessage::Segment { data: segment("seg_0000", start, end) }),
+                MockStep::Message(SidecarMessage::Progress { percent, status: "Transcribing...".to_string() }),
+            ]
+        };
+        let mut script = chunk(40, 0.0, 60.0);
+        script.extend(chunk(60, 60.0, 118.5));
+        script.push(MockStep::Crash(ExitStatus { code: Some(1), signal: None }));
+        let backend = MockBackend::new(script);
+        let mut harness = Harness::new(&backend);
+
+        let job_id = harness.start(ModelSize::Base).await;
+        harness.expect("transcription_error").await;
+        assert_eq!(harness.manager.get_job(&job_id).await.unwrap().segment_count, 2);
+
+        // The retry only asks the engine for what comes after the kept segments
+        harness.manager.retry_transcription(&job_id).await.unwrap();
+        harness.expect("transcription_error").await;
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 2);
+        let EngineRequest::Transcribe { start_offset, .. } = &requests[1];
+        assert_eq!(*start_offset, 118.5);
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_failed_save_keeps_transcript_for_retry() {
+        let backend = MockBackend::new(MockStep::transcript(
+            "en",
+            9.0,
+            vec![segment("seg_0000", 0.0, 4.0), segment("seg_0001", 4.0, 9.0)],
+        ));
+        let mut harness = Harness::new(&backend);
+
+        // A file in the way of the transcripts directory makes saving fail
+        let blocker = harness.temp_dir.path().join("data").join("transcripts");
+        std::fs::create_dir_all(blocker.parent().unwrap()).unwrap();
+        std::fs::write(&blocker, b"").unwrap();
+
+        let job_id = harness.start(ModelSize::Base).await;
+        let error = loop {
+            let (event, payload) = harness.events.recv().await.unwrap();
+            assert_ne!(event, "transcription_complete", "completed without a saved transcript");
+            if event == "transcription_error" {
+                break payload;
+            }
+        };
+        assert_eq!(error["job_id"], job_id.as_str());
+        assert_eq!(error["error"]["type"], "StorageError");
+        assert_eq!(harness.manager.get_job(&job_id).await.unwrap().state, JobState::Failed);
+        assert_eq!(harness.storage().load_partial_segments(&job_id).unwrap().len(), 2);
+
+        // Once storage works again, a retry saves the kept segments
+        std::fs::remove_file(&blocker).unwrap();
+        harness.manager.retry_transcription(&job_id).await.unwrap();
+        let complete = harness.expect("transcription_complete").await;
+        assert_eq!(complete["transcript_id"], job_id.as_str());
+        let transcript = harness.storage().load_transcript(&job_id).unwrap();
+        assert_eq!(transcript.segments.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_runs_custom_model_from_settings() {
+        let backend = MockBackend::new(MockStep::transcript("tr", 3.0, vec![segment("seg_0000", 0.0, 3.0)]));
+        let mut harness = Harness::new(&backend);
+
+        // Without a configured model, custom jobs are rejected up front
+        let media = harness.media.to_string_lossy().into_owned();
+        let error = harness
+            .manager
+            .start_transcription(harness.app.handle().clone(), media.clone(), ModelSize::Custom, 0.0, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::InvalidInput(_)));
+
+        let checkpoint = harness.temp_dir.path().join("whisper-tr-meetings.pt");
+        std::fs::write(&checkpoint, b"").unwrap();
+        let checkpoint = checkpoint.to_string_lossy().into_owned();
+        harness
+            .storage()
+            .save_settings(&Settings { custom_model: Some(checkpoint.clone()), ..Default::default() })
+            .unwrap();
+
+        let job_id = harness.start(ModelSize::Custom).await;
+        harness.expect("transcription_complete").await;
+
+        let EngineRequest::Transcribe { model, .. } = &backend.requests()[0];
+        assert_eq!(model, &checkpoint);
+        let transcript = harness.storage().load_transcript(&job_id).unwrap();
+        assert_eq!(transcript.model_size, ModelSize::Custom);
+        assert_eq!(transcript.custom_model.as_deref(), Some(checkpoint.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_rejects_missing_model_when_offline() {
+        let backend = MockBackend::new(MockStep::transcript("tr", 3.0, vec![segment("seg_0000", 0.0, 3.0)]));
+        let mut harness = Harness::new(&backend);
+        let model_dir = harness.temp_dir.path().join("whisper");
+        std::fs::create_dir(&model_dir).unwrap();
+        let manager = harness.manager.clone().with_model_manager(ModelManager::new(model_dir.clone()));
+        let media = harness.media.to_string_lossy().into_owned();
+
+        // Downloads are off by default, so the engine is never started
+        let error = manager
+            .start_transcription(harness.app.handle().clone(), media.clone(), ModelSize::Small, 0.0, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::ModelNotFound(ref m) if m.contains("small")));
+        assert!(backend.requests().is_empty());
+
+        // With downloads allowed, the engine fetches missing models itself
+        harness
+            .storage()
+            .save_settings(&Settings { allow_model_downloads: true, ..Default::default() })
+            .unwrap();
+        assert!(manager.start_transcription(harness.app.handle().clone(), media.clone(), ModelSize::Small, 0.0, None).await.is_ok());
+        harness.expect("transcription_complete").await;
+        assert_eq!(backend.requests().len(), 1);
+
+        harness.storage().save_settings(&Settings::default()).unwrap();
+        std::fs::write(model_dir.join("small.pt"), b"checkpoint").unwrap();
+        assert!(manager.start_transcription(harness.app.handle().clone(), media, ModelSize::Small, 0.0, None).await.is_ok());
+        harness.expect("transcription_complete").await;
+        assert_eq!(backend.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_saved_settings_apply_to_running_manager() {
+        let script = MockStep::transcript("en", 3.0, vec![segment("seg_0000", 0.0, 3.0)]);
+        let first = MockBackend::new(script.clone());
+        let mut harness = Harness::new(&first);
+        harness.start(ModelSize::Base).await;
+        harness.expect("transcription_complete").await;
+
+        let settings = Settings { max_concurrent_jobs: 3, watchdog_timeout_secs: 900, ..Default::default() };
+        harness.manager.apply_settings(&settings, None).await;
+        assert_eq!(harness.manager.max_concurrent_jobs(), 3);
+        assert_eq!(harness.manager.watchdog_timeout(), Duration::from_secs(900));
+
+        // The warm worker is kept while the backend stays the same
+        harness.start(ModelSize::Base).await;
+        harness.expect("transcription_complete").await;
+        assert_eq!(first.spawn_count(), 1);
+
+        // The next job runs on the new backend instead of the old worker
+        let second = MockBackend::new(script);
+        harness.manager.apply_settings(&settings, Some(Arc::new(second.clone()))).await;
+        harness.start(ModelSize::Base).await;
+        harness.expect("transcription_complete").await;
+        assert_eq!(first.requests().len(), 2);
+        assert_eq!(second.spawn_count(), 1);
+        assert_eq!(second.requests().len(), 1);
+    }
 }
@@ -1,17 +1,46 @@
 //! Storage Module for ScriptGrab
 //! JSON file-based storage for transcripts and settings
+//! Every file is replaced atomically, so a crash or a full disk never leaves
+//! it half-written, and the transcript index keeps a backup of its previous version.
+//! When the index is missing or cannot be read, it is rebuilt from the
+//! transcript files, so no transcript drops out of the history.
+//! The app shares one `StorageManager` as managed state; it serializes updates
+//! of the index, so transcripts saved or deleted at the same time are never lost.
+//! The app keeps the transcript history in a SQLite database instead (see
+//! `history_db`), importing the JSON history once; the API stays the same.
+//! Transcripts and settings are stored with a schema version; documents of
+//! older versions are upgraded by the registered migrations when loaded.
 //! Requirements: 6.1, 6.3, 9.5
 
-use crate::models::{AppError, HistoryItem, Settings, StoredTranscript};
+use crate::history_db::HistoryDb;
+use crate::models::{AppError, HistoryItem, QueueItem, Segment, Settings, StoredTranscript, TranscriptionOptions};
+use crate::throughput::ThroughputStats;
 use chrono::Utc;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::ffi::OsStr;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
 use uuid::Uuid;
 
 /// Storage manager for handling transcript and settings persistence
+///
+/// Index updates are serialized by an internal lock, which only covers this
+/// instance; everything writing to the same directory must share it.
 pub struct StorageManager {
     storage_dir: PathBuf,
+    /// Held while the index is read, modified and written back
+    index_lock: Mutex<()>,
+    /// Database holding the transcript history; without it, the history is
+    /// kept in JSON files
+    history_db: Option<HistoryDb>,
+    /// Why the history cannot be changed: the database it was moved to
+    /// cannot be opened, so the JSON files are out of date
+    read_only: Option<String>,
 }
 
 /// Index file structure for tracking all stored transcripts
@@ -20,10 +49,98 @@ pub struct TranscriptIndex {
     pub items: Vec<HistoryItem>,
 }
 
+/// Differences between the transcript index and the transcript files
+///
+/// With the history database, between its search index and the stored
+/// transcripts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    /// Readable transcript files
+    pub transcripts: usize,
+    /// IDs of transcript files missing from the index
+    pub orphans: Vec<String>,
+    /// IDs in the index whose transcript file does not exist
+    pub dangling: Vec<String>,
+    /// Transcript files that cannot be parsed or whose ID does not match
+    /// their name, or IDs of such documents in the database;
+    /// `rebuild_index` moves them to the quarantine directory
+    pub unreadable: Vec<String>,
+}
+
+impl StorageReport {
+    /// Whether the index matches the transcript files
+    pub fn is_consistent(&self) -> bool {
+        self.orphans.is_empty() && self.dangling.is_empty() && self.unreadable.is_empty()
+    }
+}
+
+/// A transcript matching a history search
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub transcript: HistoryItem,
+    /// Matching segments, in order of time
+    pub hits: Vec<SegmentHit>,
+}
+
+/// A segment matching a history search
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentHit {
+    pub segment_id: String,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Queue file structure for unfinished transcription jobs
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PersistedQueue {
+    pub items: Vec<QueueItem>,
+}
+
 impl StorageManager {
     /// Create a new storage manager with the given base directory
     pub fn new(storage_dir: PathBuf) -> Self {
-        Self { storage_dir }
+        Self {
+            storage_dir,
+            index_lock: Mutex::new(()),
+            history_db: None,
+            read_only: None,
+        }
+    }
+
+    /// Why the transcript history is read-only, if it is
+    pub fn history_read_only(&self) -> Option<&str> {
+        self.read_only.as_deref()
+    }
+
+    /// Fail if the transcript history is read-only
+    fn check_writable(&self) -> Result<(), AppError> {
+        match &self.read_only {
+            Some(reason) => Err(AppError::StorageError(format!("The history is read-only: {}", reason))),
+            None => Ok(()),
+        }
+    }
+
+    /// Keep the transcript history in the SQLite database `history.db`
+    ///
+    /// When the database is first created, every readable transcript file is
+    /// imported into it, including files missing from the JSON index. The
+    /// JSON files are left untouched.
+    pub fn with_database(mut self) -> Result<Self, AppError> {
+        let db = HistoryDb::open(&self.database_path())?;
+        if !db.json_imported()? {
+            let _guard = self.lock_index();
+            let scan = self.scan_transcripts()?;
+            if !scan.unreadable.is_empty() {
+                eprintln!("Skipping unreadable transcript files: {}", scan.unreadable.join(", "));
+            }
+            db.import_json(&scan.transcripts)?;
+        }
+        self.history_db = Some(db);
+        Ok(self)
     }
 
     /// Get the default storage directory (app data directory)
@@ -48,11 +165,46 @@ impl StorageManager {
         self.storage_dir.join("transcript_index.json")
     }
 
+    /// Get the path to the backup of the previous transcript index
+    fn index_backup_path(&self) -> PathBuf {
+        self.storage_dir.join("transcript_index.json.bak")
+    }
+
+    /// Get the path to the transcript history database
+    fn database_path(&self) -> PathBuf {
+        self.storage_dir.join("history.db")
+    }
+
     /// Get the path to the settings file
     fn settings_path(&self) -> PathBuf {
         self.storage_dir.join("settings.json")
     }
 
+    /// Get the path to the persisted job queue file
+    fn queue_path(&self) -> PathBuf {
+        self.storage_dir.join("queue.json")
+    }
+
+    /// Get the path to the per-model throughput statistics
+    fn throughput_path(&self) -> PathBuf {
+        self.storage_dir.join("throughput.json")
+    }
+
+    /// Get the path to the partial segments of an unfinished job
+    fn partial_segments_path(&self, job_id: &str) -> PathBuf {
+        self.storage_dir.join("partial").join(format!("{}.json", job_id))
+    }
+
+    /// Get the path to the directory of transcript files
+    fn transcripts_dir(&self) -> PathBuf {
+        self.storage_dir.join("transcripts")
+    }
+
+    /// Get the path to the directory unreadable transcript files are moved to
+    fn quarantine_dir(&self) -> PathBuf {
+        self.storage_dir.join("quarantine")
+    }
+
     /// Get the path to a transcript file by ID
     fn transcript_path(&self, id: &str) -> PathBuf {
         self.storage_dir.join("transcripts").join(format!("{}.json", id))
@@ -63,26 +215,87 @@ impl StorageManager {
     // ============================================
 
     /// Load the transcript index
+    ///
+    /// A missing or unreadable index is rebuilt from the transcript files.
+    /// The backup is one save behind, so it only serves as the previous
+    /// state the rebuild is reported against.
     pub fn load_index(&self) -> Result<TranscriptIndex, AppError> {
+        if let Some(db) = &self.history_db {
+            return Ok(TranscriptIndex { items: db.history()? });
+        }
+
+        let _guard = self.lock_index();
+        self.load_index_locked()
+    }
+
+    /// Lock the index for a read-modify-write cycle
+    ///
+    /// A panic while holding the lock cannot leave a half-written index
+    /// behind, so a poisoned lock is simply taken over.
+    fn lock_index(&self) -> MutexGuard<'_, ()> {
+        self.index_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Load the index; the caller must hold the index lock
+    fn load_index_locked(&self) -> Result<TranscriptIndex, AppError> {
         let path = self.index_path();
-        if !path.exists() {
+        let error = if path.exists() {
+            match read_index(&path) {
+                Ok(index) => return Ok(index),
+                Err(e) => e,
+            }
+        } else if self.has_transcript_files() {
+            AppError::StorageError("Index not found".to_string())
+        } else {
             return Ok(TranscriptIndex::default());
+        };
+
+        eprintln!("{}; rebuilding the index from the transcript files", error);
+        let previous = read_index(&self.index_backup_path()).unwrap_or_default();
+        let (index, report) = self.rebuild_index_from(&previous)?;
+        if !report.orphans.is_empty() {
+            eprintln!("Recovered transcripts missing from the index backup: {}", report.orphans.join(", "));
         }
+        Ok(index)
+    }
 
-        let content = fs::read_to_string(&path)
-            .map_err(|e| AppError::StorageError(format!("Failed to read index: {}", e)))?;
-        
-        serde_json::from_str(&content)
-            .map_err(|e| AppError::StorageError(format!("Failed to parse index: {}", e)))
+    /// Read the index, or its backup if the index cannot be read
+    ///
+    /// Returns `None` if neither file exists.
+    fn read_index_or_backup(&self) -> Result<Option<TranscriptIndex>, AppError> {
+        let path = self.index_path();
+        let backup_path = self.index_backup_path();
+        if !path.exists() && !backup_path.exists() {
+            return Ok(None);
+        }
+
+        match read_index(&path) {
+            Ok(index) => Ok(Some(index)),
+            Err(e) if backup_path.exists() => {
+                eprintln!("{}; loading the index backup", e);
+                read_index(&backup_path).map(Some)
+            }
+            Err(e) => Err(e),
+        }
     }
 
     /// Save the transcript index
+    ///
+    /// The index being replaced becomes the backup, unless it is unreadable
+    /// and the backup is the better copy.
     fn save_index(&self, index: &TranscriptIndex) -> Result<(), AppError> {
         let path = self.index_path();
         let content = serde_json::to_string_pretty(index)
             .map_err(|e| AppError::StorageError(format!("Failed to serialize index: {}", e)))?;
-        
-        fs::write(&path, content)
+
+        if let Ok(current) = fs::read(&path) {
+            if serde_json::from_slice::<TranscriptIndex>(&current).is_ok() {
+                write_atomic(&self.index_backup_path(), &current)
+                    .map_err(|e| AppError::StorageError(format!("Failed to back up index: {}", e)))?;
+            }
+        }
+
+        write_atomic(&path, content.as_bytes())
             .map_err(|e| AppError::StorageError(format!("Failed to write index: {}", e)))
     }
 
@@ -93,32 +306,29 @@ impl StorageManager {
     /// Save a transcript to storage
     /// Requirements: 6.1
     pub fn save_transcript(&self, transcript: &StoredTranscript) -> Result<(), AppError> {
+        self.check_writable()?;
+        if let Some(db) = &self.history_db {
+            return db.save(transcript);
+        }
+
         self.ensure_directories()?;
+        let _guard = self.lock_index();
 
         // Save the transcript file
         let transcript_path = self.transcript_path(&transcript.id);
-        let content = serde_json::to_string_pretty(transcript)
-            .map_err(|e| AppError::StorageError(format!("Failed to serialize transcript: {}", e)))?;
+        let content = encode_transcript(transcript)?;
         
-        fs::write(&transcript_path, content)
+        write_atomic(&transcript_path, content.as_bytes())
             .map_err(|e| AppError::StorageError(format!("Failed to write transcript: {}", e)))?;
 
         // Update the index
-        let mut index = self.load_index()?;
+        let mut index = self.load_index_locked()?;
         
         // Remove existing entry if present (for updates)
         index.items.retain(|item| item.id != transcript.id);
         
         // Add new history item
-        let history_item = HistoryItem {
-            id: transcript.id.clone(),
-            file_name: transcript.file_name.clone(),
-            file_path: transcript.file_path.clone(),
-            date: transcript.created_at.clone(),
-            duration: transcript.duration,
-            language: transcript.language.clone(),
-        };
-        index.items.push(history_item);
+        index.items.push(history_item(transcript));
         
         // Sort by date descending (newest first)
         // Requirements: 6.5
@@ -130,6 +340,10 @@ impl StorageManager {
     /// Load a transcript by ID
     /// Requirements: 6.3
     pub fn load_transcript(&self, id: &str) -> Result<StoredTranscript, AppError> {
+        if let Some(db) = &self.history_db {
+            return db.load(id);
+        }
+
         let path = self.transcript_path(id);
         if !path.exists() {
             return Err(AppError::StorageError(format!("Transcript not found: {}", id)));
@@ -138,13 +352,19 @@ impl StorageManager {
         let content = fs::read_to_string(&path)
             .map_err(|e| AppError::StorageError(format!("Failed to read transcript: {}", e)))?;
         
-        serde_json::from_str(&content)
-            .map_err(|e| AppError::StorageError(format!("Failed to parse transcript: {}", e)))
+        decode_transcript(&content)
     }
 
     /// Delete a transcript by ID
     /// Requirements: 6.4
     pub fn delete_transcript(&self, id: &str) -> Result<(), AppError> {
+        self.check_writable()?;
+        if let Some(db) = &self.history_db {
+            return db.delete(id);
+        }
+
+        let _guard = self.lock_index();
+
         // Remove the transcript file
         let path = self.transcript_path(id);
         if path.exists() {
@@ -153,7 +373,7 @@ impl StorageManager {
         }
 
         // Update the index
-        let mut index = self.load_index()?;
+        let mut index = self.load_index_locked()?;
         index.items.retain(|item| item.id != id);
         self.save_index(&index)
     }
@@ -165,6 +385,259 @@ impl StorageManager {
         Ok(index.items)
     }
 
+    /// Search the text of stored transcripts
+    ///
+    /// Segments match when they contain every word of the query, ignoring
+    /// case. The database also matches word prefixes and ignores diacritics,
+    /// and lists the best matching transcripts first; the JSON files are
+    /// searched in history order.
+    pub fn search_history(&self, query: &str) -> Result<Vec<SearchResult>, AppError> {
+        if let Some(db) = &self.history_db {
+            return db.search(query);
+        }
+
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results = Vec::new();
+        for item in self.get_history()? {
+            let Ok(transcript) = self.load_transcript(&item.id) else {
+                continue;
+            };
+            let hits: Vec<SegmentHit> = transcript
+                .segments
+                .into_iter()
+                .filter(|segment| {
+                    let text = segment.text.to_lowercase();
+                    terms.iter().all(|term| text.contains(term.as_str()))
+                })
+                .map(|segment| SegmentHit {
+                    segment_id: segment.id,
+                    start: segment.start,
+                    end: segment.end,
+                    text: segment.text,
+                })
+                .collect();
+            if !hits.is_empty() {
+                results.push(SearchResult { transcript: item, hits });
+            }
+        }
+        Ok(results)
+    }
+
+    // ============================================
+    // Consistency Checks
+    // ============================================
+
+    /// Compare the index with the transcript files without changing anything
+    ///
+    /// With the database, compares each transcript with its rows in the
+    /// search index instead.
+    pub fn verify_storage(&self) -> Result<StorageReport, AppError> {
+        if let Some(db) = &self.history_db {
+            return db.verify();
+        }
+
+        let _guard = self.lock_index();
+        let index = self.read_index_or_backup()?.unwrap_or_default();
+        let scan = self.scan_transcripts()?;
+        Ok(scan.report(&index))
+    }
+
+    /// Recreate the index from the transcript files
+    ///
+    /// Unreadable files are moved to the quarantine directory. Returns what
+    /// was out of sync; an unreadable index counts as empty. With the
+    /// database, repairs its search index the same way: transcripts missing
+    /// from it are indexed again, rows of deleted transcripts are dropped
+    /// and unreadable transcripts are moved to the quarantine directory.
+    pub fn rebuild_index(&self) -> Result<StorageReport, AppError> {
+        self.check_writable()?;
+        if let Some(db) = &self.history_db {
+            return db.rebuild(&self.quarantine_dir());
+        }
+
+        let _guard = self.lock_index();
+        let previous = self.read_index_or_backup().ok().flatten().unwrap_or_default();
+        let (_, report) = self.rebuild_index_from(&previous)?;
+        Ok(report)
+    }
+
+    /// Rebuild the index; the caller must hold the index lock
+    fn rebuild_index_from(&self, previous: &TranscriptIndex) -> Result<(TranscriptIndex, StorageReport), AppError> {
+        self.ensure_directories()?;
+        let scan = self.scan_transcripts()?;
+        let report = scan.report(previous);
+
+        for file_name in &report.unreadable {
+            self.quarantine(file_name)?;
+        }
+
+        let mut items: Vec<HistoryItem> = scan.transcripts.iter().map(history_item).collect();
+        items.sort_by(|a, b| b.date.cmp(&a.date));
+        let index = TranscriptIndex { items };
+        self.save_index(&index)?;
+
+        Ok((index, report))
+    }
+
+    /// Read every transcript file, sorting them into readable and unreadable
+    fn scan_transcripts(&self) -> Result<TranscriptScan, AppError> {
+        let mut scan = TranscriptScan::default();
+        let dir = self.transcripts_dir();
+        if !dir.exists() {
+            return Ok(scan);
+        }
+
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| AppError::StorageError(format!("Failed to read transcripts directory: {}", e)))?;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            // Skips stray temporary files of interrupted writes
+            let Some(id) = transcript_file_id(&path) else {
+                continue;
+            };
+
+            let transcript = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| decode_transcript(&content).ok())
+                .filter(|transcript| transcript.id == id);
+            match transcript {
+                Some(transcript) => scan.transcripts.push(transcript),
+                None => scan.unreadable.push(entry.file_name().to_string_lossy().into_owned()),
+            }
+        }
+
+        scan.unreadable.sort();
+        Ok(scan)
+    }
+
+    /// Whether the transcripts directory holds any transcript files
+    fn has_transcript_files(&self) -> bool {
+        fs::read_dir(self.transcripts_dir())
+            .map(|entries| entries.filter_map(|entry| entry.ok()).any(|entry| transcript_file_id(&entry.path()).is_some()))
+            .unwrap_or(false)
+    }
+
+    /// Move an unreadable transcript file out of the transcripts directory
+    ///
+    /// A file of the same name already in quarantine is kept.
+    fn quarantine(&self, file_name: &str) -> Result<(), AppError> {
+        let dir = self.quarantine_dir();
+        fs::create_dir_all(&dir)
+            .map_err(|e| AppError::StorageError(format!("Failed to create quarantine directory: {}", e)))?;
+
+        let mut target = dir.join(file_name);
+        if target.exists() {
+            target = dir.join(format!("{}.{}", file_name, Uuid::new_v4()));
+        }
+        fs::rename(self.transcripts_dir().join(file_name), &target)
+            .map_err(|e| AppError::StorageError(format!("Failed to quarantine {}: {}", file_name, e)))
+    }
+
+    // ============================================
+    // Queue Operations
+    // ============================================
+
+    /// Load the persisted job queue
+    pub fn load_queue(&self) -> Result<Vec<QueueItem>, AppError> {
+        let path = self.queue_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| AppError::StorageError(format!("Failed to read queue: {}", e)))?;
+
+        let queue: PersistedQueue = serde_json::from_str(&content)
+            .map_err(|e| AppError::StorageError(format!("Failed to parse queue: {}", e)))?;
+        Ok(queue.items)
+    }
+
+    /// Save the job queue, replacing any previously persisted items
+    pub fn save_queue(&self, items: &[QueueItem]) -> Result<(), AppError> {
+        self.ensure_directories()?;
+
+        let path = self.queue_path();
+        let queue = PersistedQueue { items: items.to_vec() };
+        let content = serde_json::to_string_pretty(&queue)
+            .map_err(|e| AppError::StorageError(format!("Failed to serialize queue: {}", e)))?;
+
+        write_atomic(&path, content.as_bytes())
+            .map_err(|e| AppError::StorageError(format!("Failed to write queue: {}", e)))
+    }
+
+    /// Save the segments a failed job received before it stopped
+    pub fn save_partial_segments(&self, job_id: &str, segments: &[Segment]) -> Result<(), AppError> {
+        let path = self.partial_segments_path(job_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::StorageError(format!("Failed to create partial directory: {}", e)))?;
+        }
+
+        let content = serde_json::to_string(segments)
+            .map_err(|e| AppError::StorageError(format!("Failed to serialize partial segments: {}", e)))?;
+
+        write_atomic(&path, content.as_bytes())
+            .map_err(|e| AppError::StorageError(format!("Failed to write partial segments: {}", e)))
+    }
+
+    /// Load the partial segments of a job (empty if none were saved)
+    pub fn load_partial_segments(&self, job_id: &str) -> Result<Vec<Segment>, AppError> {
+        let path = self.partial_segments_path(job_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| AppError::StorageError(format!("Failed to read partial segments: {}", e)))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::StorageError(format!("Failed to parse partial segments: {}", e)))
+    }
+
+    /// Delete the partial segments of a job once they are no longer needed
+    pub fn delete_partial_segments(&self, job_id: &str) -> Result<(), AppError> {
+        let path = self.partial_segments_path(job_id);
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| AppError::StorageError(format!("Failed to delete partial segments: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    // ============================================
+    // Throughput Operations
+    // ============================================
+
+    /// Load the throughput statistics of completed jobs
+    pub fn load_throughput(&self) -> Result<ThroughputStats, AppError> {
+        let path = self.throughput_path();
+        if !path.exists() {
+            return Ok(ThroughputStats::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| AppError::StorageError(format!("Failed to read throughput statistics: {}", e)))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::StorageError(format!("Failed to parse throughput statistics: {}", e)))
+    }
+
+    /// Save the throughput statistics of completed jobs
+    pub fn save_throughput(&self, stats: &ThroughputStats) -> Result<(), AppError> {
+        self.ensure_directories()?;
+
+        let path = self.throughput_path();
+        let content = serde_json::to_string_pretty(stats)
+            .map_err(|e| AppError::StorageError(format!("Failed to serialize throughput statistics: {}", e)))?;
+
+        write_atomic(&path, content.as_bytes())
+            .map_err(|e| AppError::StorageError(format!("Failed to write throughput statistics: {}", e)))
+    }
+
     // ============================================
     // Settings Operations
     // ============================================
@@ -180,8 +653,7 @@ impl StorageManager {
         let content = fs::read_to_string(&path)
             .map_err(|e| AppError::StorageError(format!("Failed to read settings: {}", e)))?;
         
-        serde_json::from_str(&content)
-            .map_err(|e| AppError::StorageError(format!("Failed to parse settings: {}", e)))
+        parse_versioned(&content, SETTINGS_MIGRATIONS, "settings")
     }
 
     /// Save settings to storage
@@ -190,14 +662,277 @@ impl StorageManager {
         self.ensure_directories()?;
         
         let path = self.settings_path();
-        let content = serde_json::to_string_pretty(settings)
+        let content = versioned_value(settings, SETTINGS_SCHEMA_VERSION)
+            .and_then(|value| serde_json::to_string_pretty(&value))
             .map_err(|e| AppError::StorageError(format!("Failed to serialize settings: {}", e)))?;
         
-        fs::write(&path, content)
+        write_atomic(&path, content.as_bytes())
             .map_err(|e| AppError::StorageError(format!("Failed to write settings: {}", e)))
     }
 }
 
+/// Transcript files found by a scan of the transcripts directory
+#[derive(Default)]
+struct TranscriptScan {
+    transcripts: Vec<StoredTranscript>,
+    /// File names of unreadable transcript files
+    unreadable: Vec<String>,
+}
+
+impl TranscriptScan {
+    /// Compare the scanned files with an index
+    fn report(&self, index: &TranscriptIndex) -> StorageReport {
+        let on_disk: HashSet<&str> = self.transcripts.iter().map(|t| t.id.as_str()).collect();
+        let indexed: HashSet<&str> = index.items.iter().map(|item| item.id.as_str()).collect();
+        let unreadable: HashSet<&str> = self.unreadable.iter().filter_map(|name| name.strip_suffix(".json")).collect();
+
+        let mut orphans: Vec<String> = on_disk.difference(&indexed).map(|id| id.to_string()).collect();
+        orphans.sort();
+        let mut dangling: Vec<String> = index
+            .items
+            .iter()
+            .filter(|item| !on_disk.contains(item.id.as_str()) && !unreadable.contains(item.id.as_str()))
+            .map(|item| item.id.clone())
+            .collect();
+        dangling.sort();
+
+        StorageReport {
+            transcripts: self.transcripts.len(),
+            orphans,
+            dangling,
+            unreadable: self.unreadable.clone(),
+        }
+    }
+}
+
+/// History entry describing a stored transcript
+fn history_item(transcript: &StoredTranscript) -> HistoryItem {
+    HistoryItem {
+        id: transcript.id.clone(),
+        file_name: transcript.file_name.clone(),
+        file_path: transcript.file_path.clone(),
+        date: transcript.created_at.clone(),
+        duration: transcript.duration,
+        language: transcript.language.clone(),
+    }
+}
+
+/// ID of the transcript stored at `path`, if it is named like a transcript file
+fn transcript_file_id(path: &Path) -> Option<String> {
+    if !path.is_file() || path.extension() != Some(OsStr::new("json")) {
+        return None;
+    }
+    path.file_stem().map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// Read and parse a transcript index file
+fn read_index(path: &Path) -> Result<TranscriptIndex, AppError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::StorageError(format!("Failed to read index: {}", e)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::StorageError(format!("Failed to parse index: {}", e)))
+}
+
+/// Replace a file's content without ever leaving it half-written
+///
+/// The content goes to a temporary file in the same directory, which is
+/// flushed to disk and then renamed over the target. A crash leaves either
+/// the old or the new content, and at most a stray `.tmp` file.
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+    #[cfg(test)]
+    let fault = write_fault::next();
+
+    let result = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            #[cfg(test)]
+            if let Some(write_fault::WriteFault::AfterBytes(len)) = fault {
+                file.write_all(&content[..len.min(content.len())])?;
+                return Err(write_fault::injected());
+            }
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|()| {
+            #[cfg(test)]
+            if let Some(write_fault::WriteFault::BeforeRename) = fault {
+                return Err(write_fault::injected());
+            }
+            fs::rename(&temp_path, path)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Failures injected into `write_atomic` by tests
+#[cfg(test)]
+mod write_fault {
+    use std::cell::Cell;
+
+    /// Where an atomic write fails
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum WriteFault {
+        /// After this many bytes of the temporary file are written
+        AfterBytes(usize),
+        /// After the temporary file is written, before it replaces the target
+        BeforeRename,
+    }
+
+    thread_local! {
+        /// Fault and the number of writes on this thread to let through first
+        static ARMED: Cell<Option<(usize, WriteFault)>> = const { Cell::new(None) };
+    }
+
+    /// Make the write after the next `skip` writes on this thread fail
+    pub fn arm(skip: usize, fault: WriteFault) {
+        ARMED.with(|armed| armed.set(Some((skip, fault))));
+    }
+
+    /// Whether the armed fault has not been hit yet
+    pub fn is_armed() -> bool {
+        ARMED.with(|armed| armed.get().is_some())
+    }
+
+    /// Fault for the write that is starting, if it should fail
+    pub fn next() -> Option<WriteFault> {
+        ARMED.with(|armed| match armed.get() {
+            Some((0, fault)) => {
+                armed.set(None);
+                Some(fault)
+            }
+            Some((skip, fault)) => {
+                armed.set(Some((skip - 1, fault)));
+                None
+            }
+            None => None,
+        })
+    }
+
+    pub fn injected() -> std::io::Error {
+        std::io::Error::other("injected write failure")
+    }
+}
+
+/// Flush a directory entry to disk, so a rename inside it survives a crash
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Windows cannot open directories for flushing; NTFS journals the rename
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+// ============================================
+// Schema Versions
+// ============================================
+
+/// Key of the schema version in stored documents
+///
+/// Documents written before versioning have no version and count as version 0.
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// Upgrade of a stored document from one schema version to the next
+type Migration = fn(&mut Map<String, Value>);
+
+/// Migrations of transcript documents; entry `n` upgrades version `n` to `n + 1`
+const TRANSCRIPT_MIGRATIONS: &[Migration] = &[transcript_v0_to_v1];
+
+/// Migrations of the settings file; entry `n` upgrades version `n` to `n + 1`
+const SETTINGS_MIGRATIONS: &[Migration] = &[settings_v0_to_v1];
+
+/// Schema version of transcript documents written by this build
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = TRANSCRIPT_MIGRATIONS.len() as u32;
+
+/// Schema version of the settings file written by this build
+pub const SETTINGS_SCHEMA_VERSION: u32 = SETTINGS_MIGRATIONS.len() as u32;
+
+/// Version 1 writes every field; unversioned transcripts may predate the
+/// custom model and transcription options
+fn transcript_v0_to_v1(document: &mut Map<String, Value>) {
+    document.entry("customModel").or_insert(Value::Null);
+    if !document.contains_key("options") {
+        let options = serde_json::to_value(TranscriptionOptions::default()).unwrap_or(Value::Null);
+        document.insert("options".to_string(), options);
+    }
+}
+
+/// Version 1 writes every field; unversioned settings files may predate any
+/// setting after the first four, which take their default values
+fn settings_v0_to_v1(document: &mut Map<String, Value>) {
+    if let Ok(Value::Object(defaults)) = serde_json::to_value(Settings::default()) {
+        for (key, value) in defaults {
+            document.entry(key).or_insert(value);
+        }
+    }
+}
+
+/// Serialize a transcript document with its schema version
+///
+/// Shared by the transcript files and the history database.
+pub fn encode_transcript(transcript: &StoredTranscript) -> Result<String, AppError> {
+    versioned_value(transcript, TRANSCRIPT_SCHEMA_VERSION)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .map_err(|e| AppError::StorageError(format!("Failed to serialize transcript: {}", e)))
+}
+
+/// Parse a transcript document of any schema version
+pub fn decode_transcript(content: &str) -> Result<StoredTranscript, AppError> {
+    parse_versioned(content, TRANSCRIPT_MIGRATIONS, "transcript")
+}
+
+/// Serialize a document into a JSON object tagged with its schema version
+fn versioned_value<T: Serialize>(document: &T, version: u32) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(document)?;
+    if let Value::Object(map) = &mut value {
+        map.insert(SCHEMA_VERSION_KEY.to_string(), version.into());
+    }
+    Ok(value)
+}
+
+/// Parse a stored document, upgrading it to the current schema version
+///
+/// Documents of a newer version than this build knows are rejected rather
+/// than loaded with their unknown fields dropped.
+fn parse_versioned<T: DeserializeOwned>(content: &str, migrations: &[Migration], kind: &str) -> Result<T, AppError> {
+    let parse_error = |e: serde_json::Error| AppError::StorageError(format!("Failed to parse {}: {}", kind, e));
+
+    let mut value: Value = serde_json::from_str(content).map_err(parse_error)?;
+    let Value::Object(document) = &mut value else {
+        return Err(AppError::StorageError(format!("Failed to parse {}: not an object", kind)));
+    };
+
+    let version = match document.remove(SCHEMA_VERSION_KEY) {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| AppError::StorageError(format!("Failed to parse {}: invalid schema version {}", kind, version)))?,
+    };
+    let Some(pending) = usize::try_from(version).ok().and_then(|version| migrations.get(version..)) else {
+        return Err(AppError::StorageError(format!(
+            "Cannot load {} of schema version {}; this version of ScriptGrab supports up to {}",
+            kind,
+            version,
+            migrations.len()
+        )));
+    };
+    for migrate in pending {
+        migrate(document);
+    }
+
+    serde_json::from_value(value).map_err(parse_error)
+}
+
 /// Generate a new unique ID for transcripts
 pub fn generate_id() -> String {
     Uuid::new_v4().to_string()
@@ -212,61 +947,90 @@ pub fn current_timestamp() -> String {
 // Tauri Commands
 // ============================================
 
-/// Get the default storage manager
+/// Create the storage manager for the default storage directory
+///
+/// Called once at startup; commands use the instance in managed state.
+/// When the history database cannot be opened, such as when it is corrupt,
+/// locked or from a newer version, the history is read from the JSON files
+/// instead. If the history had already been moved to the database, those
+/// files are out of date and the history is read-only until it opens again.
 pub fn get_storage_manager() -> Result<StorageManager, String> {
     let storage_dir = StorageManager::default_storage_dir()
         .map_err(|e| e.to_string())?;
-    Ok(StorageManager::new(storage_dir))
+    Ok(open_storage(storage_dir))
+}
+
+/// Storage manager with the history database, or with JSON files if the
+/// database cannot be opened
+fn open_storage(storage_dir: PathBuf) -> StorageManager {
+    match StorageManager::new(storage_dir.clone()).with_database() {
+        Ok(storage) => storage,
+        Err(e) => {
+            let mut storage = StorageManager::new(storage_dir);
+            if storage.database_path().exists() {
+                eprintln!("{}; the history is read-only", e);
+                storage.read_only = Some(format!("the history database cannot be opened ({})", e));
+            } else {
+                eprintln!("{}; keeping the history in JSON files", e);
+            }
+            storage
+        }
+    }
 }
 
 /// Get all history items
 /// Requirements: 6.2
 #[tauri::command]
-pub async fn get_history() -> Result<Vec<HistoryItem>, String> {
-    let storage = get_storage_manager()?;
+pub async fn get_history(storage: tauri::State<'_, Arc<StorageManager>>) -> Result<Vec<HistoryItem>, String> {
     storage.get_history().map_err(|e| e.to_string())
 }
 
 /// Delete a history item by ID
 /// Requirements: 6.4
 #[tauri::command]
-pub async fn delete_history_item(id: String) -> Result<(), String> {
-    let storage = get_storage_manager()?;
+pub async fn delete_history_item(storage: tauri::State<'_, Arc<StorageManager>>, id: String) -> Result<(), String> {
     storage.delete_transcript(&id).map_err(|e| e.to_string())
 }
 
+/// Search the text of stored transcripts
+#[tauri::command]
+pub async fn search_history(storage: tauri::State<'_, Arc<StorageManager>>, query: String) -> Result<Vec<SearchResult>, String> {
+    storage.search_history(&query).map_err(|e| e.to_string())
+}
+
+/// Check the history index against the transcript files
+#[tauri::command]
+pub async fn verify_storage(storage: tauri::State<'_, Arc<StorageManager>>) -> Result<StorageReport, String> {
+    storage.verify_storage().map_err(|e| e.to_string())
+}
+
+/// Recreate the history index from the transcript files
+#[tauri::command]
+pub async fn rebuild_index(storage: tauri::State<'_, Arc<StorageManager>>) -> Result<StorageReport, String> {
+    storage.rebuild_index().map_err(|e| e.to_string())
+}
+
 /// Load a history item (transcript) by ID
 /// Requirements: 6.3
 #[tauri::command]
-pub async fn load_history_item(id: String) -> Result<StoredTranscript, String> {
-    let storage = get_storage_manager()?;
+pub async fn load_history_item(storage: tauri::State<'_, Arc<StorageManager>>, id: String) -> Result<StoredTranscript, String> {
     storage.load_transcript(&id).map_err(|e| e.to_string())
 }
 
 /// Save a transcript to storage
 /// Requirements: 6.1
 #[tauri::command]
-pub async fn save_transcript(transcript: StoredTranscript) -> Result<(), String> {
-    let storage = get_storage_manager()?;
+pub async fn save_transcript(storage: tauri::State<'_, Arc<StorageManager>>, transcript: StoredTranscript) -> Result<(), String> {
     storage.save_transcript(&transcript).map_err(|e| e.to_string())
 }
 
 /// Get application settings
 /// Requirements: 9.5
 #[tauri::command]
-pub async fn get_settings() -> Result<Settings, String> {
-    let storage = get_storage_manager()?;
+pub async fn get_settings(storage: tauri::State<'_, Arc<StorageManager>>) -> Result<Settings, String> {
     storage.load_settings().map_err(|e| e.to_string())
 }
 
-/// Save application settings
-/// Requirements: 9.5
-#[tauri::command]
-pub async fn save_settings(settings: Settings) -> Result<(), String> {
-    let storage = get_storage_manager()?;
-    storage.save_settings(&settings).map_err(|e| e.to_string())
-}
-
 // ============================================
 // Tests
 // ============================================
@@ -274,7 +1038,7 @@ pub async fn save_settings(settings: Settings) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{ExportFormat, ModelSize, Segment, Word};
+    use crate::models::{BackendSettings, ComputeDevice, ExportFormat, JobPriority, ModelSize, QueueItemStatus, Segment, TranscriptionOptions, Word};
     use tempfile::TempDir;
 
     fn create_test_storage() -> (StorageManager, TempDir) {
@@ -292,6 +1056,8 @@ mod tests {
             duration: 120.5,
             language: "en".to_string(),
             model_size: ModelSize::Base,
+            custom_model: None,
+            options: TranscriptionOptions::default(),
             segments: vec![Segment {
                 id: "seg_001".to_string(),
                 start: 0.0,
@@ -356,19 +1122,313 @@ mod tests {
         let (storage, _temp) = create_test_storage();
         
         let settings = Settings {
-            model_size: ModelSize::Medium,
+            model_size: ModelSize::Custom,
+            custom_model: Some("/models/whisper-tr.pt".to_string()),
             minimize_to_tray: true,
             default_export_format: ExportFormat::Srt,
             auto_check_updates: false,
+            max_concurrent_jobs: 3,
+            watchdog_timeout_secs: 600,
+            device: Some(ComputeDevice::Cpu),
+            cpu_threads: Some(2),
+            cpu_budget: Some(6),
+            allow_model_downloads: true,
+            transcription_backend: BackendSettings::ExternalCommand {
+                program: "whisper-cpp-worker".to_string(),
+                args: vec!["--worker".to_string()],
+            },
+            default_transcription_options: TranscriptionOptions {
+                language: Some("tr".to_string()),
+                initial_prompt: Some("ScriptGrab".to_string()),
+                threads: Some(1),
+                ..Default::default()
+            },
         };
 
         storage.save_settings(&settings).unwrap();
         let loaded = storage.load_settings().unwrap();
 
         assert_eq!(loaded.model_size, settings.model_size);
+        assert_eq!(loaded.custom_model, settings.custom_model);
         assert_eq!(loaded.minimize_to_tray, settings.minimize_to_tray);
         assert_eq!(loaded.default_export_format, settings.default_export_format);
         assert_eq!(loaded.auto_check_updates, settings.auto_check_updates);
+        assert_eq!(loaded.max_concurrent_jobs, settings.max_concurrent_jobs);
+        assert_eq!(loaded.watchdog_timeout_secs, settings.watchdog_timeout_secs);
+        assert_eq!(loaded.device, settings.device);
+        assert_eq!(loaded.cpu_threads, settings.cpu_threads);
+        assert_eq!(loaded.cpu_budget, settings.cpu_budget);
+        assert_eq!(loaded.allow_model_downloads, settings.allow_model_downloads);
+        assert_eq!(loaded.transcription_backend, settings.transcription_backend);
+        assert_eq!(loaded.default_transcription_options, settings.default_transcription_options);
+    }
+
+    #[test]
+    fn test_save_and_load_queue() {
+        let (storage, _temp) = create_test_storage();
+
+        // Without saving, the queue is empty
+        assert!(storage.load_queue().unwrap().is_empty());
+
+        let items = vec![
+            QueueItem {
+                id: "job_1".to_string(),
+                file_path: "/path/to/a.mp3".to_string(),
+                file_name: "a.mp3".to_string(),
+                status: QueueItemStatus::Processing,
+                progress: 42.0,
+                added_at: current_timestamp(),
+                model_size: ModelSize::Small,
+                start_offset: 0.0,
+                custom_model: None,
+                options: TranscriptionOptions {
+                    language: Some("tr".to_string()),
+                    ..Default::default()
+                },
+                priority: JobPriority::High,
+            },
+            QueueItem {
+                id: "job_2".to_string(),
+                file_path: "/path/to/b.wav".to_string(),
+                file_name: "b.wav".to_string(),
+                status: QueueItemStatus::Pending,
+                progress: 0.0,
+                added_at: current_timestamp(),
+                model_size: ModelSize::Base,
+                start_offset: 0.0,
+                custom_model: None,
+                options: TranscriptionOptions::default(),
+                priority: JobPriority::Normal,
+            },
+        ];
+
+        storage.save_queue(&items).unwrap();
+        let loaded = storage.load_queue().unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].id, "job_1");
+        assert_eq!(loaded[0].status, QueueItemStatus::Processing);
+        assert_eq!(loaded[0].options.language.as_deref(), Some("tr"));
+        assert_eq!(loaded[0].model_size, ModelSize::Small);
+        assert_eq!(loaded[0].priority, JobPriority::High);
+        assert_eq!(loaded[1].id, "job_2");
+        assert_eq!(loaded[1].status, QueueItemStatus::Pending);
+    }
+
+    #[test]
+    fn test_save_and_load_throughput() {
+        let (storage, _temp) = create_test_storage();
+        assert_eq!(storage.load_throughput().unwrap(), ThroughputStats::default());
+
+        let mut stats = ThroughputStats::default();
+        stats.record("base", 600.0, 120.0);
+        stats.record("/models/whisper-tr.pt", 60.0, 90.0);
+        storage.save_throughput(&stats).unwrap();
+
+        let loaded = storage.load_throughput().unwrap();
+        assert_eq!(loaded, stats);
+        assert_eq!(loaded.real_time_factor("base"), Some(5.0));
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_no_temp_files() {
+        let (storage, temp) = create_test_storage();
+        let settings = Settings { max_concurrent_jobs: 2, ..Settings::default() };
+        storage.save_settings(&Settings::default()).unwrap();
+        storage.save_settings(&settings).unwrap();
+
+        assert_eq!(storage.load_settings().unwrap().max_concurrent_jobs, 2);
+        let stray: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(stray.is_empty());
+    }
+
+    #[test]
+    fn test_torn_index_is_rebuilt_with_latest_transcript() {
+        let (storage, _temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("first")).unwrap();
+        storage.save_transcript(&create_test_transcript("second")).unwrap();
+
+        // A torn index is rebuilt, keeping the transcript its backup is missing
+        let content = fs::read_to_string(storage.index_path()).unwrap();
+        fs::write(storage.index_path(), &content[..content.len() / 2]).unwrap();
+        let mut ids: Vec<String> = storage.get_history().unwrap().into_iter().map(|h| h.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["first", "second"]);
+        // The torn index never replaces the backup
+        assert_eq!(read_index(&storage.index_backup_path()).unwrap().items.len(), 1);
+
+        storage.save_transcript(&create_test_transcript("third")).unwrap();
+        let mut ids: Vec<String> = storage.get_history().unwrap().into_iter().map(|h| h.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["first", "second", "third"]);
+        assert!(storage.verify_storage().unwrap().is_consistent());
+
+        // Without a backup the index is rebuilt from the transcript files
+        let (storage, _temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("only")).unwrap();
+        fs::write(storage.index_path(), "{\"items\": [").unwrap();
+        let history = storage.get_history().unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, "only");
+    }
+
+    #[test]
+    fn test_verify_and_rebuild_index() {
+        let (storage, temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("kept")).unwrap();
+        storage.save_transcript(&create_test_transcript("deleted")).unwrap();
+        storage.save_transcript(&create_test_transcript("broken")).unwrap();
+        assert!(storage.verify_storage().unwrap().is_consistent());
+
+        // Out of sync: a file without entry, an entry without file and a corrupt file
+        let mut orphan = create_test_transcript("orphan");
+        orphan.created_at = "2030-01-01T00:00:00+00:00".to_string();
+        fs::write(storage.transcript_path("orphan"), serde_json::to_string(&orphan).unwrap()).unwrap();
+        fs::remove_file(storage.transcript_path("deleted")).unwrap();
+        fs::write(storage.transcript_path("broken"), "{\"id\": \"bro").unwrap();
+        fs::write(temp.path().join("transcripts").join(".kept.json.1234.tmp"), "{").unwrap();
+
+        let expected = StorageReport {
+            transcripts: 2,
+            orphans: vec!["orphan".to_string()],
+            dangling: vec!["deleted".to_string()],
+            unreadable: vec!["broken.json".to_string()],
+        };
+        assert_eq!(storage.verify_storage().unwrap(), expected);
+        // Verifying changes nothing
+        assert_eq!(storage.get_history().unwrap().len(), 3);
+
+        assert_eq!(storage.rebuild_index().unwrap(), expected);
+        let ids: Vec<String> = storage.get_history().unwrap().into_iter().map(|h| h.id).collect();
+        assert_eq!(ids, vec!["orphan", "kept"]);
+        assert!(!storage.transcript_path("broken").exists());
+        assert!(temp.path().join("quarantine").join("broken.json").exists());
+        assert!(storage.verify_storage().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_missing_index_is_rebuilt() {
+        let (storage, _temp) = create_test_storage();
+        assert!(storage.get_history().unwrap().is_empty());
+
+        storage.save_transcript(&create_test_transcript("a")).unwrap();
+        storage.save_transcript(&create_test_transcript("b")).unwrap();
+
+        // The backup still exists and lacks the newest transcript
+        fs::remove_file(storage.index_path()).unwrap();
+        assert_eq!(read_index(&storage.index_backup_path()).unwrap().items.len(), 1);
+        let mut ids: Vec<String> = storage.get_history().unwrap().into_iter().map(|h| h.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(storage.index_path().exists());
+
+        // Without a backup as well
+        fs::remove_file(storage.index_path()).unwrap();
+        fs::remove_file(storage.index_backup_path()).unwrap();
+        assert_eq!(storage.get_history().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parallel_saves_and_deletes_keep_every_entry() {
+        let (storage, _temp) = create_test_storage();
+        run_parallel_saves_and_deletes(Arc::new(storage));
+
+        let (storage, _temp) = create_test_storage();
+        run_parallel_saves_and_deletes(Arc::new(storage.with_database().unwrap()));
+    }
+
+    fn run_parallel_saves_and_deletes(storage: Arc<StorageManager>) {
+        for i in 0..8 {
+            storage.save_transcript(&create_test_transcript(&format!("old_{}", i))).unwrap();
+        }
+
+        let handles: Vec<_> = (0..8)
+            .map(|thread| {
+                let storage = Arc::clone(&storage);
+                std::thread::spawn(move || {
+                    for i in 0..10 {
+                        storage.save_transcript(&create_test_transcript(&format!("new_{}_{}", thread, i))).unwrap();
+                    }
+                    storage.delete_transcript(&format!("old_{}", thread)).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let history = storage.get_history().unwrap();
+        assert_eq!(history.len(), 80);
+        assert!(history.iter().all(|item| item.id.starts_with("new_")));
+        assert!(storage.verify_storage().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_database_imports_json_history_once() {
+        let (storage, temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("json_1")).unwrap();
+        storage.save_transcript(&create_test_transcript("json_2")).unwrap();
+        fs::write(storage.transcript_path("broken"), "{ not json").unwrap();
+        // Transcript files missing from the index are imported as well
+        let orphan = encode_transcript(&create_test_transcript("orphan")).unwrap();
+        fs::write(storage.transcript_path("orphan"), orphan).unwrap();
+
+        let storage = StorageManager::new(temp.path().to_path_buf()).with_database().unwrap();
+        let mut ids: Vec<String> = storage.get_history().unwrap().into_iter().map(|item| item.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["json_1", "json_2", "orphan"]);
+        assert_eq!(storage.load_transcript("json_1").unwrap().segments[0].text, "Hello world");
+
+        let results = storage.search_history("hello").unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].hits[0].start, 0.0);
+        assert_eq!(results[0].hits[0].end, 3.5);
+
+        // Deleted transcripts are not imported again from the JSON files
+        storage.delete_transcript("json_1").unwrap();
+        drop(storage);
+        let storage = StorageManager::new(temp.path().to_path_buf()).with_database().unwrap();
+        assert_eq!(storage.get_history().unwrap().len(), 2);
+        assert!(storage.verify_storage().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_unusable_database_falls_back_to_json_files() {
+        let (storage, temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("json_only")).unwrap();
+        fs::write(storage.database_path(), "not a database").unwrap();
+
+        // The JSON files may be out of date, so they can be read but not changed
+        let storage = open_storage(temp.path().to_path_buf());
+        assert!(storage.history_db.is_none());
+        assert!(storage.history_read_only().is_some());
+        assert_eq!(storage.get_history().unwrap()[0].id, "json_only");
+        assert!(matches!(storage.save_transcript(&create_test_transcript("lost")), Err(AppError::StorageError(ref m)) if m.contains("read-only")));
+        assert!(storage.delete_transcript("json_only").is_err());
+        assert!(storage.rebuild_index().is_err());
+        assert_eq!(storage.get_history().unwrap().len(), 1);
+
+        fs::remove_file(storage.database_path()).unwrap();
+        let storage = open_storage(temp.path().to_path_buf());
+        assert!(storage.history_db.is_some());
+        assert!(storage.history_read_only().is_none());
+    }
+
+    #[test]
+    fn test_search_json_history() {
+        let (storage, _temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("test_id")).unwrap();
+
+        let results = storage.search_history("WORLD hello").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].transcript.id, "test_id");
+        assert_eq!(results[0].hits[0].segment_id, "seg_001");
+        assert!(storage.search_history("goodbye").unwrap().is_empty());
+        assert!(storage.search_history("").unwrap().is_empty());
     }
 
     #[test]
@@ -380,6 +1440,56 @@ mod tests {
         assert_eq!(settings.model_size, ModelSize::Base);
         assert!(!settings.minimize_to_tray);
     }
+
+    #[test]
+    fn test_unversioned_documents_are_upgraded() {
+        let (storage, _temp) = create_test_storage();
+        storage.ensure_directories().unwrap();
+
+        // Written before the custom model, options and schema versions existed
+        fs::write(
+            storage.transcript_path("legacy"),
+            r#"{"id":"legacy","fileName":"a.mp3","filePath":"/a.mp3","createdAt":"2024-01-01T00:00:00Z",
+                "duration":3.0,"language":"en","modelSize":"small","segments":[]}"#,
+        )
+        .unwrap();
+        fs::write(
+            storage.settings_path(),
+            r#"{"modelSize":"medium","minimizeToTray":true,"defaultExportFormat":"srt","autoCheckUpdates":false}"#,
+        )
+        .unwrap();
+
+        let transcript = storage.load_transcript("legacy").unwrap();
+        assert_eq!(transcript.model_size, ModelSize::Small);
+        assert_eq!(transcript.custom_model, None);
+        assert_eq!(transcript.options, TranscriptionOptions::default());
+        let settings = storage.load_settings().unwrap();
+        assert_eq!(settings.default_export_format, ExportFormat::Srt);
+        assert_eq!(settings.max_concurrent_jobs, 1);
+
+        // Saving writes the current version
+        storage.save_transcript(&transcript).unwrap();
+        storage.save_settings(&settings).unwrap();
+        for path in [storage.transcript_path("legacy"), storage.settings_path()] {
+            let document: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+            assert_eq!(document[SCHEMA_VERSION_KEY], 1);
+        }
+    }
+
+    #[test]
+    fn test_newer_schema_version_is_rejected() {
+        let (storage, _temp) = create_test_storage();
+        storage.save_transcript(&create_test_transcript("future")).unwrap();
+
+        let path = storage.transcript_path("future");
+        let mut document: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        document[SCHEMA_VERSION_KEY] = (TRANSCRIPT_SCHEMA_VERSION + 1).into();
+        document["speakers"] = serde_json::json!(["Alice", "Bob"]);
+        fs::write(&path, document.to_string()).unwrap();
+
+        let error = storage.load_transcript("future").unwrap_err().to_string();
+        assert!(error.contains("schema version 2"), "{}", error);
+    }
 }
 
 
@@ -390,16 +1500,23 @@ mod tests {
 #[cfg(test)]
 mod property_tests {
     use super::*;
-    use crate::models::{ExportFormat, ModelSize, Segment, Word};
+    use super::write_fault::{self, WriteFault};
+    use crate::models::{BackendSettings, ComputeDevice, ExportFormat, ModelSize, Segment, TranscriptionOptions, TranscriptionTask, Word};
     use proptest::prelude::*;
     use tempfile::TempDir;
 
     // Arbitrary generators for test data
     fn arb_model_size() -> impl Strategy<Value = ModelSize> {
         prop_oneof![
+            Just(ModelSize::Tiny),
             Just(ModelSize::Base),
             Just(ModelSize::Small),
             Just(ModelSize::Medium),
+            Just(ModelSize::Large),
+            Just(ModelSize::LargeV2),
+            Just(ModelSize::LargeV3),
+            Just(ModelSize::Turbo),
+            Just(ModelSize::Custom),
         ]
     }
 
@@ -451,8 +1568,10 @@ mod property_tests {
             0.1f64..10000.0f64,
             "[a-z]{2}",
             arb_model_size(),
+            arb_custom_model(),
+            arb_transcription_options(),
             prop::collection::vec(arb_segment(), 0..10),
-        ).prop_map(|(id, file_name, file_path, duration, language, model_size, segments)| {
+        ).prop_map(|(id, file_name, file_path, duration, language, model_size, custom_model, options, segments)| {
             StoredTranscript {
                 id,
                 file_name,
@@ -461,27 +1580,85 @@ mod property_tests {
                 duration,
                 language,
                 model_size,
+                custom_model,
+                options,
                 segments,
             }
         })
     }
 
+    fn arb_transcription_options() -> impl Strategy<Value = TranscriptionOptions> {
+        (
+            prop::option::of("[a-z]{2,3}"),
+            prop_oneof![Just(TranscriptionTask::Transcribe), Just(TranscriptionTask::Translate)],
+            prop::option::of("[a-zA-Z0-9 ,.ğüşıöç]{1,80}"),
+            (0u8..=10).prop_map(|t| f64::from(t) / 10.0),
+            prop::option::of(arb_compute_device()),
+            prop::option::of(1usize..64),
+        ).prop_map(|(language, task, initial_prompt, temperature, device, threads)| {
+            TranscriptionOptions {
+                language,
+                task,
+                initial_prompt,
+                temperature,
+                device,
+                threads,
+            }
+        })
+    }
+
+    fn arb_compute_device() -> impl Strategy<Value = ComputeDevice> {
+        prop_oneof![Just(ComputeDevice::Cpu), Just(ComputeDevice::Cuda)]
+    }
+
     fn arb_settings() -> impl Strategy<Value = Settings> {
         (
             arb_model_size(),
+            arb_custom_model(),
             any::<bool>(),
             arb_export_format(),
             any::<bool>(),
-        ).prop_map(|(model_size, minimize_to_tray, default_export_format, auto_check_updates)| {
+            1usize..16,
+            1u64..3600,
+            (
+                prop::option::of(arb_compute_device()),
+                prop::option::of(1usize..64),
+                prop::option::of(1usize..256),
+            ),
+            any::<bool>(),
+            arb_backend_settings(),
+            arb_transcription_options(),
+        ).prop_map(|(model_size, custom_model, minimize_to_tray, default_export_format, auto_check_updates, max_concurrent_jobs, watchdog_timeout_secs, (device, cpu_threads, cpu_budget), allow_model_downloads, transcription_backend, default_transcription_options)| {
             Settings {
                 model_size,
+                custom_model,
                 minimize_to_tray,
                 default_export_format,
                 auto_check_updates,
+                max_concurrent_jobs,
+                watchdog_timeout_secs,
+                device,
+                cpu_threads,
+                cpu_budget,
+                allow_model_downloads,
+                transcription_backend,
+                default_transcription_options,
             }
         })
     }
 
+    fn arb_custom_model() -> impl Strategy<Value = Option<String>> {
+        prop::option::of("[a-zA-Z0-9_/.-]{1,60}")
+    }
+
+    fn arb_backend_settings() -> impl Strategy<Value = BackendSettings> {
+        prop_oneof![
+            Just(BackendSettings::WhisperEngine),
+            ("[a-z/._-]{1,30}", prop::collection::vec("[a-zA-Z0-9-]{0,10}", 0..4))
+                .prop_map(|(program, args)| BackendSettings::ExternalCommand { program, args }),
+        ]
+    }
+
     /// Helper function for approximate floating-point comparison
     /// Handles JSON serialization precision loss
     fn approx_eq(a: f64, b: f64) -> bool {
@@ -489,6 +1666,14 @@ mod property_tests {
         (a - b).abs() < EPSILON
     }
 
+    /// A write that fails part-way through the data or just before the rename
+    fn arb_write_fault() -> impl Strategy<Value = WriteFault> {
+        prop_oneof![
+            (0usize..4096).prop_map(WriteFault::AfterBytes),
+            Just(WriteFault::BeforeRename),
+        ]
+    }
+
     // Feature: scriptgrab-transcriber, Property 11: Storage Round-Trip
     // *For any* valid transcript with metadata, saving to storage and then loading by ID 
     // SHALL produce an equivalent transcript with identical metadata.
@@ -496,13 +1681,19 @@ mod property_tests {
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(100))]
         #[test]
-        fn prop_storage_round_trip(transcript in arb_stored_transcript()) {
+        fn prop_storage_round_trip(transcript in arb_stored_transcript(), update in arb_stored_transcript(), fault in arb_write_fault()) {
             let temp_dir = TempDir::new().unwrap();
             let storage = StorageManager::new(temp_dir.path().to_path_buf());
 
             // Save transcript
             storage.save_transcript(&transcript).unwrap();
 
+            // An update of the transcript fails while the file is written
+            let update = StoredTranscript { id: transcript.id.clone(), ..update };
+            write_fault::arm(0, fault);
+            prop_assert!(storage.save_transcript(&update).is_err());
+            prop_assert!(!write_fault::is_armed());
+
             // Load transcript
             let loaded = storage.load_transcript(&transcript.id).unwrap();
 
@@ -514,6 +1705,8 @@ mod property_tests {
                 "Duration mismatch: {} vs {}", loaded.duration, transcript.duration);
             prop_assert_eq!(loaded.language, transcript.language);
             prop_assert_eq!(loaded.model_size, transcript.model_size);
+            prop_assert_eq!(loaded.custom_model, transcript.custom_model);
+            prop_assert_eq!(loaded.options, transcript.options);
             prop_assert_eq!(loaded.segments.len(), transcript.segments.len());
 
             // Verify segments with approximate float comparison
@@ -545,21 +1738,90 @@ mod property_tests {
     proptest! {
         #![proptest_config(ProptestConfig::with_cases(100))]
         #[test]
-        fn prop_settings_round_trip(settings in arb_settings()) {
+        fn prop_settings_round_trip(settings in arb_settings(), fault in arb_write_fault()) {
             let temp_dir = TempDir::new().unwrap();
             let storage = StorageManager::new(temp_dir.path().to_path_buf());
 
             // Save settings
             storage.save_settings(&settings).unwrap();
 
+            // The next save fails while the file is written
+            write_fault::arm(0, fault);
+            prop_assert!(storage.save_settings(&Settings::default()).is_err());
+            prop_assert!(!write_fault::is_armed());
+
             // Load settings
             let loaded = storage.load_settings().unwrap();
 
             // Verify equivalence
             prop_assert_eq!(loaded.model_size, settings.model_size);
+            prop_assert_eq!(loaded.custom_model, settings.custom_model);
             prop_assert_eq!(loaded.minimize_to_tray, settings.minimize_to_tray);
             prop_assert_eq!(loaded.default_export_format, settings.default_export_format);
             prop_assert_eq!(loaded.auto_check_updates, settings.auto_check_updates);
+            prop_assert_eq!(loaded.max_concurrent_jobs, settings.max_concurrent_jobs);
+            prop_assert_eq!(loaded.watchdog_timeout_secs, settings.watchdog_timeout_secs);
+            prop_assert_eq!(loaded.device, settings.device);
+            prop_assert_eq!(loaded.cpu_threads, settings.cpu_threads);
+            prop_assert_eq!(loaded.cpu_budget, settings.cpu_budget);
+            prop_assert_eq!(loaded.allow_model_downloads, settings.allow_model_downloads);
+            prop_assert_eq!(loaded.transcription_backend, settings.transcription_backend);
+            prop_assert_eq!(loaded.default_transcription_options, settings.default_transcription_options);
+        }
+    }
+
+    // *For any* sequence of saved transcripts, an index write that fails SHALL
+    // leave the previous index and its backup intact, and an index torn by an
+    // earlier non-atomic write SHALL fall back to the backup and be rebuilt with
+    // every transcript.
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(50))]
+        #[test]
+        fn prop_index_survives_interrupted_write(
+            transcripts in prop::collection::vec(arb_stored_transcript(), 2..6),
+            new_transcript in arb_stored_transcript(),
+            fault in arb_write_fault(),
+            cut in any::<prop::sample::Index>(),
+        ) {
+            let temp_dir = TempDir::new().unwrap();
+            let storage = StorageManager::new(temp_dir.path().to_path_buf());
+
+            let mut ids = Vec::new();
+            for (i, mut t) in transcripts.into_iter().enumerate() {
+                t.id = format!("unique_id_{}", i);
+                storage.save_transcript(&t).unwrap();
+                ids.push(t.id);
+            }
+            let history_ids = |storage: &StorageManager| {
+                let mut ids: Vec<String> = storage.get_history().unwrap().into_iter().map(|h| h.id).collect();
+                ids.sort();
+                ids
+            };
+
+            let index_ids = |index: TranscriptIndex| {
+                let mut ids: Vec<String> = index.items.into_iter().map(|item| item.id).collect();
+                ids.sort();
+                ids
+            };
+
+            // Saving writes the transcript file, the index backup and then the
+            // index; the index write fails
+            let new_transcript = StoredTranscript { id: "unique_id_new".to_string(), ..new_transcript };
+            write_fault::arm(2, fault);
+            prop_assert!(storage.save_transcript(&new_transcript).is_err());
+            prop_assert!(!write_fault::is_armed());
+            prop_assert_eq!(history_ids(&storage), ids.clone());
+            prop_assert_eq!(index_ids(read_index(&storage.index_backup_path()).unwrap()), ids.clone());
+
+            // Any strict prefix of the index is invalid JSON; loading falls back
+            // to the backup and recovers the transcript the failed save wrote
+            let content = fs::read(storage.index_path()).unwrap();
+            let torn = &content[..cut.index(content.len())];
+            fs::write(storage.index_path(), torn).unwrap();
+            prop_assert_eq!(index_ids(storage.read_index_or_backup().unwrap().unwrap()), ids.clone());
+            ids.push(new_transcript.id);
+            ids.sort();
+            prop_assert_eq!(history_ids(&storage), ids);
         }
     }
 
@@ -630,4 +1892,145 @@ mod property_tests {
             }
         }
     }
+
+    /// Fields added before schema versions existed; unversioned transcripts
+    /// may lack any of them
+    const UNVERSIONED_TRANSCRIPT_FIELDS: &[&str] = &["customModel", "options"];
+
+    /// Settings added before schema versions existed
+    const UNVERSIONED_SETTINGS_FIELDS: &[&str] = &[
+        "customModel",
+        "maxConcurrentJobs",
+        "watchdogTimeoutSecs",
+        "device",
+        "cpuThreads",
+        "cpuBudget",
+        "allowModelDownloads",
+        "transcriptionBackend",
+        "defaultTranscriptionOptions",
+    ];
+
+    /// Transcription options added after the options themselves
+    const UNVERSIONED_OPTION_FIELDS: &[&str] = &["device", "threads"];
+
+    /// A document as an earlier release would have written `current`
+    ///
+    /// Version 0 documents have no schema version and lack the `missing`
+    /// fields, and the `missing_options` fields of their nested options.
+    fn historical_document<T: Serialize>(current: &T, version: u32, missing: &[&str], options_key: &str, missing_options: &[&str]) -> String {
+        let mut document = serde_json::to_value(current).unwrap();
+        let map = document.as_object_mut().unwrap();
+        if version == 0 {
+            for field in missing {
+                map.remove(*field);
+            }
+            if let Some(Value::Object(options)) = map.get_mut(options_key) {
+                for field in missing_options {
+                    options.remove(*field);
+                }
+            }
+        } else {
+            map.insert(SCHEMA_VERSION_KEY.to_string(), version.into());
+        }
+        document.to_string()
+    }
+
+    /// `document` as it reads back from JSON
+    ///
+    /// Parsing may round floats by one ulp, so compare loaded documents
+    /// with this instead of the original.
+    fn json_round_trip<T: Serialize>(document: &T) -> Value {
+        serde_json::from_str(&serde_json::to_string(document).unwrap()).unwrap()
+    }
+
+    /// `options` as loaded from a document lacking the `missing` fields
+    fn expected_options(options: &mut TranscriptionOptions, missing: &[&str]) {
+        for field in missing {
+            match *field {
+                "device" => options.device = None,
+                "threads" => options.threads = None,
+                _ => unreachable!("unknown option field {}", field),
+            }
+        }
+    }
+
+    // *For any* transcript, a document of every historical schema version
+    // SHALL load into the current model, with fields the version lacked set
+    // to their defaults.
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(100))]
+        #[test]
+        fn prop_historical_transcripts_load(
+            transcript in arb_stored_transcript(),
+            version in 0..=TRANSCRIPT_SCHEMA_VERSION,
+            missing in prop::sample::subsequence(UNVERSIONED_TRANSCRIPT_FIELDS, 0..=UNVERSIONED_TRANSCRIPT_FIELDS.len()),
+            missing_options in prop::sample::subsequence(UNVERSIONED_OPTION_FIELDS, 0..=UNVERSIONED_OPTION_FIELDS.len()),
+        ) {
+            let temp_dir = TempDir::new().unwrap();
+            let storage = StorageManager::new(temp_dir.path().to_path_buf());
+            storage.ensure_directories().unwrap();
+
+            let document = historical_document(&transcript, version, &missing, "options", &missing_options);
+            fs::write(storage.transcript_path(&transcript.id), document).unwrap();
+
+            let mut expected = transcript.clone();
+            if version == 0 {
+                expected_options(&mut expected.options, &missing_options);
+                for field in &missing {
+                    match *field {
+                        "customModel" => expected.custom_model = None,
+                        "options" => expected.options = TranscriptionOptions::default(),
+                        _ => unreachable!("unknown transcript field {}", field),
+                    }
+                }
+            }
+
+            let loaded = storage.load_transcript(&transcript.id).unwrap();
+            prop_assert_eq!(serde_json::to_value(&loaded).unwrap(), json_round_trip(&expected));
+        }
+    }
+
+    // *For any* settings, a settings file of every historical schema version
+    // SHALL load into the current model, with settings the version lacked set
+    // to their defaults.
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(100))]
+        #[test]
+        fn prop_historical_settings_load(
+            settings in arb_settings(),
+            version in 0..=SETTINGS_SCHEMA_VERSION,
+            missing in prop::sample::subsequence(UNVERSIONED_SETTINGS_FIELDS, 0..=UNVERSIONED_SETTINGS_FIELDS.len()),
+            missing_options in prop::sample::subsequence(UNVERSIONED_OPTION_FIELDS, 0..=UNVERSIONED_OPTION_FIELDS.len()),
+        ) {
+            let temp_dir = TempDir::new().unwrap();
+            let storage = StorageManager::new(temp_dir.path().to_path_buf());
+            storage.ensure_directories().unwrap();
+
+            let document = historical_document(&settings, version, &missing, "defaultTranscriptionOptions", &missing_options);
+            fs::write(storage.settings_path(), document).unwrap();
+
+            let mut expected = settings.clone();
+            if version == 0 {
+                expected_options(&mut expected.default_transcription_options, &missing_options);
+                let defaults = Settings::default();
+                for field in &missing {
+                    match *field {
+                        "customModel" => expected.custom_model = defaults.custom_model.clone(),
+                        "maxConcurrentJobs" => expected.max_concurrent_jobs = defaults.max_concurrent_jobs,
+                        "watchdogTimeoutSecs" => expected.watchdog_timeout_secs = defaults.watchdog_timeout_secs,
+                        "device" => expected.device = defaults.device,
+                        "cpuThreads" => expected.cpu_threads = defaults.cpu_threads,
+                        "cpuBudget" => expected.cpu_budget = defaults.cpu_budget,
+                        "allowModelDownloads" => expected.allow_model_downloads = defaults.allow_model_downloads,
+                        "transcriptionBackend" => expected.transcription_backend = defaults.transcription_backend.clone(),
+                        "defaultTranscriptionOptions" => expected.default_transcription_options = defaults.default_transcription_options.clone(),
+                        _ => unreachable!("unknown settings field {}", field),
+                    }
+                }
+            }
+
+            let loaded = storage.load_settings().unwrap();
+            prop_assert_eq!(serde_json::to_value(&loaded).unwrap(), json_round_trip(&expected));
+        }
+    }
 }
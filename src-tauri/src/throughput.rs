@@ -0,0 +1,152 @@
+//! Transcription Throughput Module
+//!
+//! Measures how fast jobs are transcribed as a real-time factor: seconds of
+//! audio per wall-clock second. A running job is estimated from the end of
+//! its latest segment; before its first segment arrives, the average of
+//! earlier jobs with the same model is used instead. The averages persist
+//! between sessions, so a job can be estimated before it starts.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Timing of a running job
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct ProgressEstimate {
+    /// Seconds the current attempt has been running, excluding time spent paused
+    pub elapsed_seconds: f64,
+    /// Estimated seconds until the job completes, when it can be estimated
+    pub eta_seconds: Option<f64>,
+    /// Seconds of audio transcribed per wall-clock second in this attempt
+    pub real_time_factor: Option<f64>,
+}
+
+/// Total work done by completed jobs of one model
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ModelThroughput {
+    /// Seconds of audio transcribed
+    pub audio_seconds: f64,
+    /// Wall-clock seconds spent transcribing it, including model loading but not time paused
+    pub wall_seconds: f64,
+    /// Number of completed jobs
+    pub jobs: u32,
+}
+
+impl ModelThroughput {
+    /// Average seconds of audio per wall-clock second
+    pub fn real_time_factor(&self) -> Option<f64> {
+        (self.audio_seconds > 0.0 && self.wall_seconds > 0.0).then(|| self.audio_seconds / self.wall_seconds)
+    }
+}
+
+/// Throughput of completed jobs, per model
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ThroughputStats {
+    pub models: HashMap<String, ModelThroughput>,
+}
+
+impl ThroughputStats {
+    /// Add a completed job to the averages of its model
+    ///
+    /// Jobs without audio or measurable wall time are ignored.
+    pub fn record(&mut self, model: &str, audio_seconds: f64, wall_seconds: f64) {
+        if !(audio_seconds.is_finite() && wall_seconds.is_finite() && audio_seconds > 0.0 && wall_seconds > 0.0) {
+            return;
+        }
+
+        let entry = self.models.entry(model.to_string()).or_default();
+        entry.audio_seconds += audio_seconds;
+        entry.wall_seconds += wall_seconds;
+        entry.jobs += 1;
+    }
+
+    /// Average real-time factor of a model, if it has completed any jobs
+    pub fn real_time_factor(&self, model: &str) -> Option<f64> {
+        self.models.get(model).and_then(ModelThroughput::real_time_factor)
+    }
+}
+
+/// Estimate the time left for a running job
+///
+/// `transcribed_until` is the end of the latest segment and `start_offset`
+/// where the current attempt started, so only audio of this attempt counts
+/// towards its speed. Until the job has produced a segment, `average_rtf`
+/// predicts the total time of the attempt.
+pub fn estimate_progress(
+    elapsed_seconds: f64,
+    start_offset: f64,
+    transcribed_until: Option<f64>,
+    media_duration: Option<f64>,
+    average_rtf: Option<f64>,
+) -> ProgressEstimate {
+    let elapsed_seconds = elapsed_seconds.max(0.0);
+
+    let real_time_factor = transcribed_until
+        .map(|end| end - start_offset)
+        .filter(|&transcribed| transcribed > 0.0 && elapsed_seconds > 0.0)
+        .map(|transcribed| transcribed / elapsed_seconds);
+
+    let eta_seconds = match (media_duration, transcribed_until, real_time_factor) {
+        (Some(duration), Some(end), Some(rate)) => Some((duration - end).max(0.0) / rate),
+        (Some(duration), _, None) => average_rtf
+            .filter(|&rate| rate > 0.0)
+            .map(|rate| ((duration - start_offset).max(0.0) / rate - elapsed_seconds).max(0.0)),
+        _ => None,
+    };
+
+    ProgressEstimate {
+        elapsed_seconds,
+        eta_seconds,
+        real_time_factor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_averages_per_model() {
+        let mut stats = ThroughputStats::default();
+        assert_eq!(stats.real_time_factor("base"), None);
+
+        stats.record("base", 600.0, 100.0);
+        stats.record("base", 300.0, 200.0);
+        stats.record("small", 60.0, 60.0);
+        // Nothing to learn from empty or unmeasured jobs
+        stats.record("small", 0.0, 10.0);
+        stats.record("small", 10.0, f64::NAN);
+
+        assert_eq!(stats.real_time_factor("base"), Some(3.0));
+        assert_eq!(stats.models["base"].jobs, 2);
+        assert_eq!(stats.real_time_factor("small"), Some(1.0));
+        assert_eq!(stats.models["small"].jobs, 1);
+    }
+
+    #[test]
+    fn test_estimate_from_latest_segment() {
+        // 120s of audio in 60s from the start of the file
+        let estimate = estimate_progress(60.0, 0.0, Some(120.0), Some(600.0), Some(10.0));
+        assert_eq!(estimate.real_time_factor, Some(2.0));
+        assert_eq!(estimate.eta_seconds, Some(240.0));
+
+        // A resumed attempt only counts audio after its start offset
+        let estimate = estimate_progress(30.0, 300.0, Some(330.0), Some(600.0), None);
+        assert_eq!(estimate.real_time_factor, Some(1.0));
+        assert_eq!(estimate.eta_seconds, Some(270.0));
+    }
+
+    #[test]
+    fn test_estimate_before_first_segment_uses_model_average() {
+        let estimate = estimate_progress(20.0, 0.0, None, Some(600.0), Some(5.0));
+        assert_eq!(estimate.real_time_factor, None);
+        assert_eq!(estimate.eta_seconds, Some(100.0));
+
+        // Slower than usual: the estimate bottoms out instead of going negative
+        let estimate = estimate_progress(500.0, 0.0, None, Some(600.0), Some(5.0));
+        assert_eq!(estimate.eta_seconds, Some(0.0));
+
+        // Unknown model speed or media duration
+        assert_eq!(estimate_progress(20.0, 0.0, None, Some(600.0), None).eta_seconds, None);
+        assert_eq!(estimate_progress(20.0, 0.0, Some(60.0), None, Some(5.0)).eta_seconds, None);
+    }
+}
@@ -0,0 +1,277 @@
+//! Warm Sidecar Worker Module
+//!
+//! A worker is a backend process, such as whisper-engine started with
+//! `--worker`. It reads job requests from stdin, one JSON object per line, and
+//! keeps the last used model loaded, so a batch of files pays for model
+//! loading only once.
+//! A worker runs one job at a time; everything it writes to stdout belongs to
+//! that job until the job's `complete` or `error` message.
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use crate::backend::BackendProcess;
+use crate::models::{AppError, EngineInfo, EngineRequest};
+
+/// How long an idle worker is kept before its process is stopped
+pub const WORKER_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// State shared between a worker and the task reading its output
+#[derive(Debug, Default)]
+pub struct WorkerState {
+    /// Job the worker is currently running
+    pub job_id: Option<String>,
+    /// Handshake the worker sent when it started
+    pub engine: Option<EngineInfo>,
+}
+
+/// Handle to a worker's shared state
+#[derive(Debug, Clone, Default)]
+pub struct SharedWorkerState(Arc<Mutex<WorkerState>>);
+
+impl SharedWorkerState {
+    pub fn lock(&self) -> MutexGuard<'_, WorkerState> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get the job the worker is currently running
+    pub fn job_id(&self) -> Option<String> {
+        self.lock().job_id.clone()
+    }
+
+    fn same_as(&self, other: &SharedWorkerState) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A running backend worker
+#[derive(Debug)]
+pub struct Worker {
+    process: Box<dyn BackendProcess>,
+    state: SharedWorkerState,
+    idle_since: Instant,
+    retired: bool,
+}
+
+impl Worker {
+    pub fn new(process: Box<dyn BackendProcess>, state: SharedWorkerState) -> Self {
+        Self {
+            process,
+            state,
+            idle_since: Instant::now(),
+            retired: false,
+        }
+    }
+
+    /// OS process ID, or `None` if the backend cannot pause its workers
+    pub fn pid(&self) -> Option<u32> {
+        self.process.pid()
+    }
+
+    /// Hand a job to the worker
+    ///
+    /// Returns the worker's handshake if it already arrived; otherwise the
+    /// handshake is the first message reported for this job.
+    pub fn start(&mut self, job_id: &str, request: &EngineRequest) -> Result<Option<EngineInfo>, AppError> {
+        // Route output to the job before the worker can produce any
+        let engine = {
+            let mut state = self.state.lock();
+            state.job_id = Some(job_id.to_string());
+            state.engine.clone()
+        };
+
+        if let Err(e) = self.process.send(request) {
+            self.state.lock().job_id = None;
+            return Err(e);
+        }
+
+        Ok(engine)
+    }
+
+    /// Mark the worker idle after its job ended
+    pub fn release(&mut self) {
+        self.state.lock().job_id = None;
+        self.idle_since = Instant::now();
+    }
+
+    /// Stop the worker once its current job ends instead of keeping it idle,
+    /// e.g. after the backend it was started by has been replaced
+    pub fn retire(&mut self) {
+        self.retired = true;
+    }
+
+    pub fn kill(self) -> Result<(), AppError> {
+        self.process.kill()
+    }
+}
+
+/// Idle workers waiting for their next job
+#[derive(Debug, Default)]
+pub struct WorkerPool {
+    idle: Vec<Worker>,
+}
+
+impl WorkerPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the most recently used idle worker, whose model is most likely still useful
+    pub fn take(&mut self) -> Option<Worker> {
+        self.idle.pop()
+    }
+
+    /// Keep an idle worker for later, unless it is retired or `capacity`
+    /// workers are already idle
+    pub fn put(&mut self, worker: Worker, capacity: usize) {
+        if !worker.retired && self.idle.len() < capacity {
+            self.idle.push(worker);
+        } else {
+            let _ = worker.kill();
+        }
+    }
+
+    /// Forget a worker whose process exited
+    pub fn remove(&mut self, state: &SharedWorkerState) {
+        self.idle.retain(|worker| !worker.state.same_as(state));
+    }
+
+    /// Stop every idle worker
+    pub fn clear(&mut self) {
+        for worker in self.idle.drain(..) {
+            let _ = worker.kill();
+        }
+    }
+
+    /// Stop workers that have been idle too long or exceed `capacity`
+    pub fn reap(&mut self, now: Instant, capacity: usize) {
+        let mut kept = Vec::with_capacity(self.idle.len());
+        // Newest workers are at the end and are kept first
+        for worker in std::mem::take(&mut self.idle).into_iter().rev() {
+            let expired = now.saturating_duration_since(worker.idle_since) > WORKER_IDLE_TIMEOUT;
+            if expired || kept.len() >= capacity {
+                let _ = worker.kill();
+            } else {
+                kept.push(worker);
+            }
+        }
+        kept.reverse();
+        self.idle = kept;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TranscriptionTask;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Backend process that records requests and kills
+    #[derive(Debug, Default)]
+    struct FakeProcess {
+        requests: Vec<EngineRequest>,
+        kills: Arc<AtomicUsize>,
+    }
+
+    impl BackendProcess for FakeProcess {
+        fn pid(&self) -> Option<u32> {
+            None
+        }
+
+        fn send(&mut self, request: &EngineRequest) -> Result<(), AppError> {
+            self.requests.push(request.clone());
+            Ok(())
+        }
+
+        fn kill(self: Box<Self>) -> Result<(), AppError> {
+            self.kills.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn worker(kills: &Arc<AtomicUsize>) -> (Worker, SharedWorkerState) {
+        let state = SharedWorkerState::default();
+        let process = FakeProcess { kills: kills.clone(), ..Default::default() };
+        (Worker::new(Box::new(process), state.clone()), state)
+    }
+
+    fn request(job_id: &str) -> EngineRequest {
+        EngineRequest::Transcribe {
+            job_id: job_id.to_string(),
+            audio_path: "/in/a.mp3".to_string(),
+            model: "base".to_string(),
+            start_offset: 0.0,
+            language: None,
+            task: TranscriptionTask::Transcribe,
+            initial_prompt: None,
+            temperature: 0.0,
+            device: None,
+            threads: None,
+        }
+    }
+
+    #[test]
+    fn test_worker_routes_output_to_its_job() {
+        let kills = Arc::new(AtomicUsize::new(0));
+        let (mut worker, state) = worker(&kills);
+
+        assert_eq!(worker.start("job-1", &request("job-1")).unwrap(), None);
+        assert_eq!(state.job_id().as_deref(), Some("job-1"));
+
+        worker.release();
+        assert_eq!(state.job_id(), None);
+
+        // A handshake received earlier is handed to the next job
+        let engine = EngineInfo {
+            protocol_version: 1,
+            engine_version: "0.1.0".to_string(),
+            models: vec!["base".to_string()],
+            devices: vec!["cpu".to_string()],
+        };
+        state.lock().engine = Some(engine.clone());
+        assert_eq!(worker.start("job-2", &request("job-2")).unwrap(), Some(engine));
+    }
+
+    #[test]
+    fn test_pool_respects_capacity_and_idle_timeout() {
+        let kills = Arc::new(AtomicUsize::new(0));
+        let mut pool = WorkerPool::new();
+
+        let (first, first_state) = worker(&kills);
+        let (second, _) = worker(&kills);
+        pool.put(first, 1);
+        pool.put(second, 1);
+        assert_eq!(kills.load(Ordering::SeqCst), 1);
+
+        // A worker whose process exited is forgotten
+        pool.remove(&first_state);
+        assert!(pool.take().is_none());
+
+        let (third, _) = worker(&kills);
+        pool.put(third, 2);
+        pool.reap(Instant::now(), 2);
+        assert_eq!(kills.load(Ordering::SeqCst), 1);
+
+        pool.reap(Instant::now() + WORKER_IDLE_TIMEOUT + Duration::from_secs(1), 2);
+        assert_eq!(kills.load(Ordering::SeqCst), 2);
+        assert!(pool.take().is_none());
+    }
+
+    #[test]
+    fn test_pool_stops_retired_workers() {
+        let kills = Arc::new(AtomicUsize::new(0));
+        let mut pool = WorkerPool::new();
+
+        let (idle, _) = worker(&kills);
+        pool.put(idle, 2);
+        pool.clear();
+        assert_eq!(kills.load(Ordering::SeqCst), 1);
+        assert!(pool.take().is_none());
+
+        // A worker retired during its job is stopped when the job ends
+        let (mut running, _) = worker(&kills);
+        running.retire();
+        pool.put(running, 2);
+        assert_eq!(kills.load(Ordering::SeqCst), 2);
+        assert!(pool.take().is_none());
+    }
+}
@@ -18,9 +18,14 @@ interface SettingsPanelProps {
 
 // Model size options with descriptions
 const MODEL_OPTIONS: { value: ModelSize; label: string; description: string; ram: string }[] = [
+  { value: 'tiny', label: 'Tiny', description: 'En hızlı, taslak kalitesinde', ram: '~1GB RAM' },
   { value: 'base', label: 'Base', description: 'Hızlı işlem, temel doğruluk', ram: '~1GB RAM' },
   { value: 'small', label: 'Small', description: 'Dengeli performans', ram: '~2GB RAM' },
   { value: 'medium', label: 'Medium', description: 'Yüksek doğruluk', ram: '~5GB RAM' },
+  { value: 'turbo', label: 'Turbo', description: 'Large v3 doğruluğuna yakın, çok daha hızlı', ram: '~6GB RAM' },
+  { value: 'large-v2', label: 'Large v2', description: 'En yüksek doğruluk, önceki sürüm', ram: '~10GB RAM' },
+  { value: 'large-v3', label: 'Large v3', description: 'En yüksek doğruluk', ram: '~10GB RAM' },
+  { value: 'custom', label: 'Özel model', description: 'İnce ayarlı bir model dosyası veya model adı', ram: '' },
 ];
 
 const SettingsPanel: React.FC<SettingsPanelProps> = ({ isOpen, onClose }) => {
@@ -60,6 +65,16 @@ const SettingsPanel: React.FC<SettingsPanelProps> = ({ isOpen, onClose }) => {
     saveSettings({ modelSize });
   }, [saveSettings]);
 
+  // Handle custom model path or name change
+  const handleCustomModelChange = useCallback((customModel: string) => {
+    saveSettings({ customModel: customModel.trim() === '' ? null : customModel });
+  }, [saveSettings]);
+
+  // Handle model downloads toggle
+  const handleAllowModelDownloadsChange = useCallback((allowModelDownloads: boolean) => {
+    saveSettings({ allowModelDownloads });
+  }, [saveSettings]);
+
   // Handle minimize to tray toggle
   const handleMinimizeToTrayChange = useCallback((minimizeToTray: boolean) => {
     saveSettings({ minimizeToTray });
@@ -154,6 +169,37 @@ const SettingsPanel: React.FC<SettingsPanelProps> = ({ isOpen, onClose }) => {
                     </label>
                   ))}
                 </div>
+                {settings.modelSize === 'custom' && (
+                  <input
+                    type="text"
+                    value={settings.customModel ?? ''}
+                    onChange={(e) => handleCustomModelChange(e.target.value)}
+                    placeholder="/path/to/model.pt veya içe aktarılan model adı"
+                    className="mt-3 w-full px-3 py-2 rounded-lg bg-zinc-800 border border-zinc-700 text-sm text-zinc-200 placeholder-zinc-500 focus:outline-none focus:border-indigo-500"
+                  />
+                )}
+                <label className="mt-3 flex items-center justify-between p-3 rounded-lg bg-zinc-800/50 border border-zinc-700/50 cursor-pointer hover:bg-zinc-800 transition-colors">
+                  <div>
+                    <span className="block text-sm font-medium text-zinc-200">Eksik modelleri indir</span>
+                    <span className="block text-xs text-zinc-500 mt-0.5">
+                      Kapalıyken yüklü olmayan modeller indirilmeden hata verir
+                    </span>
+                  </div>
+                  <button
+                    role="switch"
+                    aria-checked={settings.allowModelDownloads}
+                    onClick={() => handleAllowModelDownloadsChange(!settings.allowModelDownloads)}
+                    className={`relative w-11 h-6 rounded-full transition-colors ${
+                      settings.allowModelDownloads ? 'bg-indigo-600' : 'bg-zinc-700'
+                    }`}
+                  >
+                    <span
+                      className={`absolute top-0.5 left-0.5 w-5 h-5 bg-white rounded-full shadow transition-transform ${
+                        settings.allowModelDownloads ? 'translate-x-5' : 'translate-x-0'
+                      }`}
+                    />
+                  </button>
+                </label>
               </section>
 
               {/* System Behavior */}
@@ -92,9 +92,25 @@ interface AppActions {
 
 const defaultSettings: Settings = {
   modelSize: 'base',
+  customModel: null,
   minimizeToTray: false,
   defaultExportFormat: 'txt',
   autoCheckUpdates: true,
+  maxConcurrentJobs: 1,
+  watchdogTimeoutSecs: 300,
+  device: null,
+  cpuThreads: null,
+  cpuBudget: null,
+  allowModelDownloads: false,
+  transcriptionBackend: { kind: 'whisperEngine' },
+  defaultTranscriptionOptions: {
+    language: null,
+    task: 'transcribe',
+    initialPrompt: null,
+    temperature: 0,
+    device: null,
+    threads: null,
+  },
 };
 
 const initialState: AppState = {
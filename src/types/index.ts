@@ -85,14 +85,49 @@ export interface StoredTranscript {
   duration: number;
   language: string;
   modelSize: ModelSize;
+  customModel?: string | null;
+  options?: TranscriptionOptions;
   segments: Segment[];
 }
 
+/**
+ * Differences between the history index and the transcript files; with the
+ * history database, between its search index and the stored transcripts
+ */
+export interface StorageReport {
+  /** Readable transcript files */
+  transcripts: number;
+  /** IDs of transcript files missing from the index */
+  orphans: string[];
+  /** IDs in the index whose transcript file does not exist */
+  dangling: string[];
+  /** Unreadable transcript files, or IDs of unreadable transcripts in the database; rebuilding moves them to quarantine */
+  unreadable: string[];
+}
+
+/**
+ * A segment matching a history search
+ */
+export interface SegmentHit {
+  segmentId: string;
+  start: number;
+  end: number;
+  text: string;
+}
+
+/**
+ * A transcript matching a history search, with its matching segments in order of time
+ */
+export interface SearchResult {
+  transcript: HistoryItem;
+  hits: SegmentHit[];
+}
+
 // ============================================
 // Queue Types
 // ============================================
 
-export type QueueItemStatus = 'pending' | 'processing' | 'completed' | 'error';
+export type QueueItemStatus = 'pending' | 'processing' | 'completed' | 'error' | 'interrupted';
 
 /**
  * Queue item for batch processing
@@ -104,13 +139,48 @@ export interface QueueItem {
   status: QueueItemStatus;
   progress: number;
   addedAt: string;
+  modelSize?: ModelSize;
+  startOffset?: number;
+  customModel?: string | null;
+  options?: TranscriptionOptions;
+  priority?: JobPriority;
 }
 
 // ============================================
 // Settings Types
 // ============================================
 
-export type ModelSize = 'base' | 'small' | 'medium';
+export type ModelSize =
+  | 'tiny'
+  | 'base'
+  | 'small'
+  | 'medium'
+  | 'large'
+  | 'large-v2'
+  | 'large-v3'
+  | 'turbo'
+  | 'custom';
+
+export type TranscriptionTask = 'transcribe' | 'translate';
+
+export type ComputeDevice = 'cpu' | 'cuda';
+
+/**
+ * Language, task and decoding options of a transcription job
+ */
+export interface TranscriptionOptions {
+  /** Language code such as 'tr'; null detects the language */
+  language: string | null;
+  task: TranscriptionTask;
+  /** Names and vocabulary that prime the decoder */
+  initialPrompt: string | null;
+  /** Sampling temperature from 0 to 1 */
+  temperature: number;
+  /** Inference device; null uses the device from settings */
+  device: ComputeDevice | null;
+  /** CPU threads; null uses the thread count from settings */
+  threads: number | null;
+}
 
 /**
  * Application settings
@@ -118,11 +188,34 @@ export type ModelSize = 'base' | 'small' | 'medium';
  */
 export interface Settings {
   modelSize: ModelSize;
+  /** Checkpoint path, or name of an imported checkpoint, used by the 'custom' model size */
+  customModel: string | null;
   minimizeToTray: boolean;
   defaultExportFormat: ExportFormat;
   autoCheckUpdates: boolean;
+  maxConcurrentJobs: number;
+  /** Seconds without progress before a job is considered hung */
+  watchdogTimeoutSecs: number;
+  /** Device jobs run on by default; null uses CUDA when available */
+  device: ComputeDevice | null;
+  /** CPU threads per job; null uses the job's share of the CPU budget */
+  cpuThreads: number | null;
+  /** CPU threads shared by all running jobs; null uses every core */
+  cpuBudget: number | null;
+  /** Whether missing models may be downloaded; off by default */
+  allowModelDownloads: boolean;
+  transcriptionBackend: BackendSettings;
+  /** Options for jobs started without their own */
+  defaultTranscriptionOptions: TranscriptionOptions;
 }
 
+/**
+ * Engine that runs transcription jobs
+ */
+export type BackendSettings =
+  | { kind: 'whisperEngine' }
+  | { kind: 'externalCommand'; program: string; args: string[] };
+
 // ============================================
 // Application State Types
 // ============================================
@@ -141,6 +234,14 @@ export interface AppState {
 // Sidecar Message Types (Python -> Rust -> Frontend)
 // ============================================
 
+export interface HelloMessage {
+  type: 'hello';
+  protocol_version: number;
+  engine_version: string;
+  models: string[];
+  devices: string[];
+}
+
 export interface ProgressMessage {
   type: 'progress';
   percent: number;
@@ -163,7 +264,12 @@ export interface ErrorMessage {
   message: string;
 }
 
-export type SidecarMessage = ProgressMessage | SegmentMessage | CompleteMessage | ErrorMessage;
+export type SidecarMessage =
+  | HelloMessage
+  | ProgressMessage
+  | SegmentMessage
+  | CompleteMessage
+  | ErrorMessage;
 
 // ============================================
 // Transcription Event Payload Types (Rust -> Frontend)
@@ -177,6 +283,12 @@ export interface TranscriptionProgressPayload {
   job_id: string;
   percent: number;
   status: string;
+  /** Seconds the current attempt has been running, excluding time paused */
+  elapsed_seconds: number;
+  /** Estimated seconds until the job completes, when known */
+  eta_seconds: number | null;
+  /** Seconds of audio transcribed per wall-clock second */
+  real_time_factor: number | null;
 }
 
 /**
@@ -196,15 +308,114 @@ export interface TranscriptionCompletePayload {
   job_id: string;
   language: string;
   duration: number;
+  /** ID of the history entry saved by the backend, if any */
+  transcript_id: string | null;
 }
 
 /**
  * Event payload for transcription error
  * Requirements: 2.8
  */
+/**
+ * Classified backend error, mirrors the Rust AppError enum
+ */
+export type AppErrorType =
+  | 'FileNotFound'
+  | 'UnsupportedFormat'
+  | 'TranscriptionFailed'
+  | 'StorageError'
+  | 'ModelNotFound'
+  | 'ModelDownloadFailed'
+  | 'SidecarError'
+  | 'FFmpegNotFound'
+  | 'InvalidInput'
+  | 'OutOfMemory'
+  | 'DecodeFailed'
+  | 'ProcessKilled'
+  | 'Timeout';
+
+export interface AppError {
+  type: AppErrorType;
+  message?: string;
+}
+
 export interface TranscriptionErrorPayload {
   job_id: string;
   message: string;
+  error: AppError;
+  /** Last lines the sidecar wrote to stderr, oldest first */
+  stderr_tail: string[];
+}
+
+/**
+ * Lifecycle state of a backend transcription job
+ */
+export type JobState = 'queued' | 'running' | 'paused' | 'interrupted' | 'failed';
+
+/**
+ * Scheduling priority of a backend job; higher priority jobs are queued first
+ */
+export type JobPriority = 'low' | 'normal' | 'high';
+
+/**
+ * Full status of a backend transcription job
+ */
+export interface JobInfo {
+  job_id: string;
+  file_path: string;
+  file_name: string;
+  model_size: ModelSize;
+  state: JobState;
+  priority: JobPriority;
+  percent: number;
+  status: string;
+  added_at: string;
+  started_at: string | null;
+  elapsed_seconds: number | null;
+  eta_seconds: number | null;
+  real_time_factor: number | null;
+  segment_count: number;
+  start_offset: number;
+}
+
+/**
+ * Total work done by completed jobs of one model
+ */
+export interface ModelThroughput {
+  audio_seconds: number;
+  wall_seconds: number;
+  jobs: number;
+}
+
+/**
+ * Throughput of completed jobs, keyed by model name or checkpoint path
+ */
+export interface ThroughputStats {
+  models: Record<string, ModelThroughput>;
+}
+
+/**
+ * A single job entry in the backend queue snapshot
+ */
+export interface QueueEntryPayload {
+  job_id: string;
+  file_path: string;
+  model_size: ModelSize;
+  state: JobState;
+  priority: JobPriority;
+}
+
+/**
+ * Event payload for backend queue state changes
+ */
+export interface QueueChangedPayload {
+  jobs: QueueEntryPayload[];
+  running: number;
+  paused: number;
+  queued: number;
+  interrupted: number;
+  failed: number;
+  max_concurrent: number;
 }
 
 // ============================================
@@ -213,8 +424,25 @@ export interface TranscriptionErrorPayload {
 
 export interface ModelInfo {
   name: ModelSize;
+  /** Approximate download size, for display */
   size: string;
   downloaded: boolean;
+  /** Bytes the checkpoint takes in the model cache; 0 when not downloaded */
+  sizeOnDisk: number;
+  /** Where the checkpoint is stored; imported custom models are listed with name 'custom' */
+  path: string | null;
+}
+
+export type ModelIntegrity = 'valid' | 'corrupt' | 'missing';
+
+/**
+ * Result of checking a downloaded model against its published checksum
+ */
+export interface ModelVerification {
+  name: ModelSize;
+  integrity: ModelIntegrity;
+  expectedSha256: string;
+  actualSha256: string | null;
 }
 
 // ============================================
@@ -237,7 +465,23 @@ export interface FFmpegCheckResult {
 
 export const DEFAULT_SETTINGS: Settings = {
   modelSize: 'base',
+  customModel: null,
   minimizeToTray: false,
   defaultExportFormat: 'txt',
   autoCheckUpdates: true,
+  maxConcurrentJobs: 1,
+  watchdogTimeoutSecs: 300,
+  device: null,
+  cpuThreads: null,
+  cpuBudget: null,
+  allowModelDownloads: false,
+  transcriptionBackend: { kind: 'whisperEngine' },
+  defaultTranscriptionOptions: {
+    language: null,
+    task: 'transcribe',
+    initialPrompt: null,
+    temperature: 0,
+    device: null,
+    threads: null,
+  },
 };
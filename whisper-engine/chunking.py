@@ -0,0 +1,111 @@
+"""
+ScriptGrab Whisper Engine - Chunked Transcription Module
+
+Whisper decodes a whole file in one blocking call, so a long recording would
+produce no output until it is done. The file is instead transcribed in chunks
+of CHUNK_SECONDS, and the segments of every chunk are emitted as soon as it is
+decoded, followed by a progress message. A job that fails part-way has then
+kept everything before the failure and is resumed from there, and the app's
+watchdog can consider an engine hung once it stays silent for longer than it
+takes to decode one chunk.
+"""
+
+from typing import Callable, Optional
+
+
+# Sample rate of audio loaded by whisper
+SAMPLE_RATE = 16000
+
+# Seconds of audio decoded per chunk; the app's watchdog allows for one chunk
+CHUNK_SECONDS = 60.0
+
+
+def shift_segment(segment: dict, offset: float) -> dict:
+    """
+    Shift a whisper segment and its words by a time offset.
+
+    Used when transcription starts part-way into the file, so that the
+    emitted timestamps stay relative to the start of the original file.
+
+    Args:
+        segment: Raw segment from whisper-timestamped
+        offset: Offset in seconds to add to every timestamp
+
+    Returns:
+        The same segment with shifted timestamps
+    """
+    if offset <= 0:
+        return segment
+
+    segment["start"] = segment.get("start", 0.0) + offset
+    segment["end"] = segment.get("end", 0.0) + offset
+    for word_data in segment.get("words", []):
+        word_data["start"] = word_data.get("start", 0.0) + offset
+        word_data["end"] = word_data.get("end", 0.0) + offset
+    return segment
+
+
+def transcribe_in_chunks(
+    audio,
+    transcribe_chunk: Callable[[object, Optional[str], Optional[str]], dict],
+    on_segment: Callable[[dict], None],
+    on_progress: Callable[[float], None],
+    start_offset: float = 0.0,
+    language: Optional[str] = None,
+    initial_prompt: Optional[str] = None,
+    chunk_seconds: float = CHUNK_SECONDS
+) -> Optional[str]:
+    """
+    Transcribe audio chunk by chunk, starting at an offset.
+
+    The last segment of a chunk may be cut off at the chunk's end, so it is
+    dropped and the next chunk starts where the segment before it ended.
+    Chunks after the first keep the detected language and are primed with the
+    text of the previous segment.
+
+    Args:
+        audio: Samples of the whole file at SAMPLE_RATE
+        transcribe_chunk: Transcribes samples with a language and prompt, returning
+            whisper's result with timestamps relative to the samples
+        on_segment: Called with each segment as soon as its chunk is decoded, with
+            timestamps relative to the start of the file
+        on_progress: Called with the position in seconds transcribed up to
+        start_offset: Seconds to skip at the start of the file
+        language: Language code of the speech, or None to detect it
+        initial_prompt: Text that primes the decoder with names and vocabulary
+        chunk_seconds: Seconds of audio per chunk
+
+    Returns:
+        The language of the speech
+    """
+    total_duration = len(audio) / SAMPLE_RATE
+    position = start_offset
+    prompt = initial_prompt
+
+    while position < total_duration:
+        chunk_end = min(position + chunk_seconds, total_duration)
+        result = transcribe_chunk(
+            audio[int(position * SAMPLE_RATE):int(chunk_end * SAMPLE_RATE)],
+            language,
+            prompt
+        )
+        language = language or result.get("language")
+
+        chunk_segments = result.get("segments", [])
+        is_last = chunk_end >= total_duration
+        if not is_last and len(chunk_segments) > 1:
+            chunk_segments = chunk_segments[:-1]
+        chunk_segments = [shift_segment(segment, position) for segment in chunk_segments]
+        for segment in chunk_segments:
+            on_segment(segment)
+
+        # A chunk without speech, or one that ended nowhere, moves on to the next
+        next_position = chunk_segments[-1].get("end", 0.0) if chunk_segments and not is_last else chunk_end
+        position = next_position if next_position > position else chunk_end
+
+        if chunk_segments:
+            last_text = chunk_segments[-1].get("text", "").strip()
+            prompt = " ".join(part for part in (initial_prompt, last_text) if part) or None
+        on_progress(position)
+
+    return language
@@ -6,14 +6,23 @@ This module provides the core transcription functionality using OpenAI's Whisper
 with word-level timestamps via whisper-timestamped.
 
 JSON stdout protocol:
+- Hello: {"type": "hello", "protocol_version": N, "engine_version": "...", "models": [...], "devices": [...]}
 - Progress: {"type": "progress", "percent": N, "status": "..."}
 - Segment: {"type": "segment", "data": {...}}
 - Complete: {"type": "complete", "language": "...", "duration": N}
 - Error: {"type": "error", "message": "..."}
+
+Worker mode (--worker) keeps the process and the loaded model alive between
+files. Jobs are read from stdin, one JSON object per line:
+- Transcribe: {"type": "transcribe", "job_id": "...", "audio_path": "...", "model": "base", "start_offset": 0.0,
+  "language": null, "task": "transcribe", "initial_prompt": null, "temperature": 0.0,
+  "device": null, "threads": null}
+Each job ends with a complete or error message. The worker exits when stdin closes.
 """
 
 import sys
 import os
+import json
 import argparse
 from pathlib import Path
 from typing import Optional
@@ -56,7 +65,9 @@ finally:
     # Restore stdout for our JSON protocol
     sys.stdout = _original_stdout
 
+from chunking import SAMPLE_RATE, transcribe_in_chunks
 from protocol import (
+    emit_hello,
     emit_progress,
     emit_segment_dict,
     emit_complete,
@@ -64,6 +75,41 @@ from protocol import (
 )
 
 
+# Model names accepted by --model; a path to a checkpoint file also works
+MODEL_SIZES = ["tiny", "base", "small", "medium", "large", "large-v2", "large-v3", "turbo"]
+
+# Tasks accepted by --task
+TASKS = ["transcribe", "translate"]
+
+# Devices accepted by --device
+DEVICES = ["cpu", "cuda"]
+
+
+def available_devices() -> list:
+    """List the inference devices usable on this machine."""
+    devices = ["cpu"]
+    try:
+        import torch
+        if torch.cuda.is_available():
+            devices.append("cuda")
+    except Exception:
+        pass
+    return devices
+
+
+def is_model_cached(model: str) -> bool:
+    """Whether a named model's checkpoint is already in whisper's download cache."""
+    cache_root = os.path.join(os.getenv("XDG_CACHE_HOME", os.path.expanduser("~/.cache")), "whisper")
+    import whisper as openai_whisper  # whisper-timestamped builds on openai-whisper
+    url = getattr(openai_whisper, "_MODELS", {}).get(model)
+    return url is not None and os.path.isfile(os.path.join(cache_root, os.path.basename(url)))
+
+
+def is_known_model(model: str) -> bool:
+    """Whether a model is a known model name or an existing checkpoint file."""
+    return model in MODEL_SIZES or Path(model).is_file()
+
+
 def format_segment(segment: dict, segment_index: int) -> dict:
     """
     Format a whisper segment into the expected output format.
@@ -107,52 +153,114 @@ def get_audio_duration(audio_path: str) -> float:
     """
     try:
         audio = whisper.load_audio(audio_path)
-        # Audio is loaded at 16kHz sample rate
-        duration = len(audio) / 16000.0
+        duration = len(audio) / SAMPLE_RATE
         return duration
     except Exception:
         return 0.0
 
 
+class ModelCache:
+    """Keeps the most recently used Whisper model loaded between jobs."""
+    
+    def __init__(self):
+        self._key = None
+        self._model = None
+    
+    def get(self, model_size: str, device: str):
+        """Return the model for this size and device, loading it if needed."""
+        key = (model_size, device)
+        if self._key != key:
+            # Drop the old model first so two models never share memory
+            self._key = None
+            self._model = None
+            
+            # Suppress model loading output
+            _temp_stdout = sys.stdout
+            sys.stdout = StderrRedirector()
+            try:
+                self._model = whisper.load_model(model_size, device=device)
+            except Exception as e:
+                # A named model that is not cached has to be downloaded first;
+                # without a network that fails with an unhelpful URL error
+                if model_size in MODEL_SIZES and not is_model_cached(model_size):
+                    raise RuntimeError(
+                        f"Model not found: {model_size} is not installed and could not be downloaded ({e})"
+                    ) from e
+                raise
+            finally:
+                sys.stdout = _temp_stdout
+            self._key = key
+        return self._model
+
+
 class TranscriptionProgressCallback:
-    """Callback class to track transcription progress."""
+    """Reports progress as chunks of the file are transcribed."""
     
-    def __init__(self, total_duration: float):
+    # Percent reached once the model is loaded, and reserved for completion
+    START_PERCENT = 20
+    END_PERCENT = 99
+    
+    def __init__(self, total_duration: float, start_offset: float = 0.0):
         self.total_duration = total_duration
-        self.last_percent = 0
+        self.start_offset = start_offset
+        self.last_percent = self.START_PERCENT
     
     def __call__(self, current_time: float) -> None:
-        """Called during transcription with current timestamp."""
-        if self.total_duration > 0:
-            percent = int((current_time / self.total_duration) * 100)
-            percent = min(percent, 99)  # Reserve 100% for completion
-            
-            # Only emit if percent changed significantly
-            if percent > self.last_percent:
-                self.last_percent = percent
-                emit_progress(percent, f"Transcribing... {percent}%")
+        """Called after each chunk with the position transcribed up to."""
+        remaining = self.total_duration - self.start_offset
+        if remaining <= 0:
+            return
+        
+        fraction = min(max((current_time - self.start_offset) / remaining, 0.0), 1.0)
+        percent = self.START_PERCENT + int(fraction * (self.END_PERCENT - self.START_PERCENT))
+        
+        # Always report, even without a change in percent: every message tells
+        # the app's watchdog that the engine is still working
+        self.last_percent = max(percent, self.last_percent)
+        emit_progress(self.last_percent, f"Transcribing... {self.last_percent}%")
 
 
 def transcribe_audio(
     audio_path: str,
     model_size: str = "base",
-    device: Optional[str] = None
-) -> None:
+    device: Optional[str] = None,
+    start_offset: float = 0.0,
+    models: Optional[ModelCache] = None,
+    language: Optional[str] = None,
+    task: str = "transcribe",
+    initial_prompt: Optional[str] = None,
+    temperature: float = 0.0,
+    threads: Optional[int] = None
+) -> bool:
     """
     Transcribe an audio file using Whisper with word-level timestamps.
     
     Emits progress, segment, and completion messages via stdout JSON protocol.
+    Failures are reported with an error message.
     
     Args:
         audio_path: Path to the audio/video file
-        model_size: Whisper model size (tiny, base, small, medium, large)
+        model_size: Whisper model name or path to a checkpoint file
         device: Device to use (cpu, cuda, or None for auto-detect)
+        start_offset: Seconds to skip at the start of the file (used to resume)
+        models: Cache that keeps the model loaded for the next call
+        language: Language code of the speech, or None to auto-detect
+        task: "transcribe", or "translate" to translate into English
+        initial_prompt: Text that primes the decoder with names and vocabulary
+        temperature: Sampling temperature, 0 for deterministic decoding
+        threads: CPU threads for inference, or None for the torch default
+        
+    Returns:
+        True if the transcription completed
     """
+    if models is None:
+        models = ModelCache()
+    
     try:
         # Validate file exists
         if not Path(audio_path).exists():
             emit_error(f"File not found: {audio_path}")
-            sys.exit(1)
+            return False
         
         # Step 1: Load audio and get duration
         emit_progress(5, "Loading audio file...")
@@ -160,65 +268,141 @@ def transcribe_audio(
         
         if total_duration <= 0:
             emit_error("Could not determine audio duration")
-            sys.exit(1)
+            return False
+        
+        if start_offset >= total_duration:
+            emit_error(f"Start offset {start_offset:.3f}s is past the end of the audio")
+            return False
         
         # Step 2: Load model
         emit_progress(10, f"Loading Whisper {model_size} model...")
         
+        import torch
+        
         # Auto-detect device if not specified
         if device is None:
-            import torch
             device = "cuda" if torch.cuda.is_available() else "cpu"
         
-        # Suppress model loading output
-        _stderr_redirector = StderrRedirector()
-        _temp_stdout = sys.stdout
-        sys.stdout = _stderr_redirector
-        try:
-            model = whisper.load_model(model_size, device=device)
-        finally:
-            sys.stdout = _temp_stdout
+        # Jobs running side by side each get their share of the cores
+        if threads is not None:
+            torch.set_num_threads(threads)
+        
+        model = models.get(model_size, device)
             
         emit_progress(20, "Model loaded, starting transcription...")
         
-        # Step 3: Transcribe with word-level timestamps
+        # Step 3: Transcribe with word-level timestamps, chunk by chunk
         audio = whisper.load_audio(audio_path)
         
-        # Suppress transcription output
-        _temp_stdout2 = sys.stdout
-        sys.stdout = _stderr_redirector
-        try:
-            result = whisper.transcribe(
-                model,
-                audio,
-                language=None,  # Auto-detect language
-                vad=False,  # Disable VAD to avoid silero dependency issues
-                detect_disfluencies=False,
-                compute_word_confidence=False
-            )
-        finally:
-            sys.stdout = _temp_stdout2
+        def transcribe_chunk(samples, chunk_language, prompt):
+            # Suppress transcription output
+            _temp_stdout2 = sys.stdout
+            sys.stdout = StderrRedirector()
+            try:
+                return whisper.transcribe(
+                    model,
+                    samples,
+                    language=chunk_language,  # None auto-detects the language
+                    task=task,
+                    initial_prompt=prompt,
+                    temperature=temperature,
+                    vad=False,  # Disable VAD to avoid silero dependency issues
+                    detect_disfluencies=False,
+                    compute_word_confidence=False
+                )
+            finally:
+                sys.stdout = _temp_stdout2
         
-        # Step 4: Process and emit segments
-        emit_progress(90, "Processing segments...")
+        # Segments are emitted as they are decoded, so a failure keeps the ones before it
+        segment_count = 0
         
-        detected_language = result.get("language", "unknown")
-        segments = result.get("segments", [])
+        def on_segment(segment):
+            nonlocal segment_count
+            emit_segment_dict(format_segment(segment, segment_count))
+            segment_count += 1
         
-        for idx, segment in enumerate(segments):
-            formatted_segment = format_segment(segment, idx)
-            emit_segment_dict(formatted_segment)
+        detected_language = transcribe_in_chunks(
+            audio,
+            transcribe_chunk,
+            on_segment,
+            TranscriptionProgressCallback(total_duration, start_offset),
+            start_offset=start_offset,
+            language=language,
+            initial_prompt=initial_prompt
+        )
         
-        # Step 5: Complete
+        # Step 4: Complete
         emit_progress(100, "Transcription complete")
-        emit_complete(detected_language, total_duration)
+        emit_complete(detected_language or "unknown", total_duration)
+        return True
         
-    except KeyboardInterrupt:
-        emit_error("Transcription cancelled by user")
-        sys.exit(130)
     except Exception as e:
         emit_error(str(e))
-        sys.exit(1)
+        return False
+
+
+def run_worker(device: Optional[str] = None) -> None:
+    """
+    Serve transcription jobs read from stdin until stdin closes.
+    
+    The model stays loaded between jobs, so only the first job of a batch
+    pays for loading it.
+    
+    Args:
+        device: Device for jobs that don't choose one (cpu, cuda, or None for auto-detect)
+    """
+    models = ModelCache()
+    
+    while True:
+        line = sys.stdin.readline()
+        if not line:
+            break
+        line = line.strip()
+        if not line:
+            continue
+        
+        try:
+            request = json.loads(line)
+        except json.JSONDecodeError as e:
+            emit_error(f"Invalid job request: {e}")
+            continue
+        
+        if request.get("type") != "transcribe" or "audio_path" not in request:
+            emit_error(f"Unsupported job request: {line}")
+            continue
+        
+        model_size = request.get("model", "base")
+        if not is_known_model(model_size):
+            emit_error(f"Model not found: {model_size}")
+            continue
+        
+        task = request.get("task") or "transcribe"
+        if task not in TASKS:
+            emit_error(f"Unsupported task: {task}")
+            continue
+        
+        job_device = request.get("device") or device
+        if job_device is not None and job_device not in DEVICES:
+            emit_error(f"Unsupported device: {job_device}")
+            continue
+        
+        threads = request.get("threads")
+        if threads is not None and int(threads) < 1:
+            emit_error(f"Thread count must be at least 1: {threads}")
+            continue
+        
+        transcribe_audio(
+            audio_path=request["audio_path"],
+            model_size=model_size,
+            device=job_device,
+            start_offset=max(0.0, float(request.get("start_offset", 0.0))),
+            models=models,
+            language=request.get("language") or None,
+            task=task,
+            initial_prompt=request.get("initial_prompt") or None,
+            temperature=float(request.get("temperature") or 0.0),
+            threads=int(threads) if threads is not None else None
+        )
 
 
 def main():
@@ -229,30 +413,103 @@ def main():
     parser.add_argument(
         "audio_path",
         type=str,
+        nargs="?",
         help="Path to the audio or video file to transcribe"
     )
+    parser.add_argument(
+        "--worker",
+        action="store_true",
+        help="Read transcription jobs from stdin and keep the model loaded between them"
+    )
     parser.add_argument(
         "--model",
         type=str,
         default="base",
-        choices=["tiny", "base", "small", "medium", "large"],
-        help="Whisper model size (default: base)"
+        help=f"Whisper model ({', '.join(MODEL_SIZES)}) or path to a checkpoint file (default: base)"
     )
     parser.add_argument(
         "--device",
         type=str,
         default=None,
-        choices=["cpu", "cuda"],
+        choices=DEVICES,
         help="Device to use for inference (default: auto-detect)"
     )
+    parser.add_argument(
+        "--threads",
+        type=int,
+        default=None,
+        help="CPU threads to use for inference (default: torch default)"
+    )
+    parser.add_argument(
+        "--start-offset",
+        type=float,
+        default=0.0,
+        help="Start transcribing this many seconds into the file (default: 0)"
+    )
+    parser.add_argument(
+        "--language",
+        type=str,
+        default=None,
+        help="Language code of the speech, e.g. tr (default: auto-detect)"
+    )
+    parser.add_argument(
+        "--task",
+        type=str,
+        default="transcribe",
+        choices=TASKS,
+        help="Transcribe as spoken or translate to English (default: transcribe)"
+    )
+    parser.add_argument(
+        "--initial-prompt",
+        type=str,
+        default=None,
+        help="Text that primes the decoder, such as names and vocabulary"
+    )
+    parser.add_argument(
+        "--temperature",
+        type=float,
+        default=0.0,
+        help="Sampling temperature from 0 to 1 (default: 0)"
+    )
     
     args = parser.parse_args()
     
-    transcribe_audio(
-        audio_path=args.audio_path,
-        model_size=args.model,
-        device=args.device
-    )
+    if not is_known_model(args.model):
+        parser.error(f"Model not found: {args.model}")
+    if args.start_offset < 0:
+        parser.error("--start-offset must not be negative")
+    if not 0.0 <= args.temperature <= 1.0:
+        parser.error("--temperature must be between 0 and 1")
+    if args.threads is not None and args.threads < 1:
+        parser.error("--threads must be at least 1")
+    if not args.worker and args.audio_path is None:
+        parser.error("audio_path is required unless --worker is given")
+    
+    # The backend checks this before trusting any other message
+    emit_hello(MODEL_SIZES, available_devices())
+    
+    try:
+        if args.worker:
+            run_worker(device=args.device)
+            return
+        
+        completed = transcribe_audio(
+            audio_path=args.audio_path,
+            model_size=args.model,
+            device=args.device,
+            start_offset=args.start_offset,
+            language=args.language,
+            task=args.task,
+            initial_prompt=args.initial_prompt,
+            temperature=args.temperature,
+            threads=args.threads
+        )
+    except KeyboardInterrupt:
+        emit_error("Transcription cancelled by user")
+        sys.exit(130)
+    
+    if not completed:
+        sys.exit(1)
 
 
 if __name__ == "__main__":
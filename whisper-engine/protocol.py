@@ -4,6 +4,7 @@ ScriptGrab Whisper Engine - JSON Protocol Module
 Defines the JSON stdout protocol for communication with the Tauri backend.
 
 Message Types:
+- hello: First message of every run, announcing protocol and engine versions
 - progress: Real-time progress updates during transcription
 - segment: Individual transcript segments with word-level timestamps
 - complete: Final completion message with metadata
@@ -16,6 +17,13 @@ from dataclasses import dataclass, asdict
 from typing import List, Optional
 
 
+# Version of the JSON protocol; bump on any incompatible message change
+PROTOCOL_VERSION = 3
+
+# Version of this engine build
+ENGINE_VERSION = "0.1.0"
+
+
 @dataclass
 class Word:
     """Represents a single word with timestamps."""
@@ -63,6 +71,27 @@ class ProtocolEmitter:
         """Emit a JSON message to stdout."""
         print(json.dumps(message), flush=True)
     
+    @staticmethod
+    def hello(models: List[str], devices: List[str]) -> None:
+        """
+        Emit the handshake message, before any other message.
+        
+        Args:
+            models: Model sizes this engine can run
+            devices: Inference devices available on this machine
+        
+        Output format:
+            {"type": "hello", "protocol_version": N, "engine_version": "...",
+             "models": [...], "devices": [...]}
+        """
+        ProtocolEmitter._emit({
+            "type": "hello",
+            "protocol_version": PROTOCOL_VERSION,
+            "engine_version": ENGINE_VERSION,
+            "models": models,
+            "devices": devices
+        })
+    
     @staticmethod
     def progress(percent: int, status: str) -> None:
         """
@@ -149,6 +178,7 @@ class ProtocolEmitter:
 
 
 # Convenience functions for direct import
+emit_hello = ProtocolEmitter.hello
 emit_progress = ProtocolEmitter.progress
 emit_segment = ProtocolEmitter.segment
 emit_segment_dict = ProtocolEmitter.segment_dict
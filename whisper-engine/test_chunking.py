@@ -0,0 +1,102 @@
+"""
+Tests for chunked transcription, runnable without whisper or torch:
+
+    python -m unittest test_chunking
+"""
+
+import unittest
+
+from chunking import SAMPLE_RATE, transcribe_in_chunks
+
+
+def silence(seconds: float) -> list:
+    """Samples of an audio file of the given length."""
+    return [0.0] * int(seconds * SAMPLE_RATE)
+
+
+class FakeWhisper:
+    """Splits every chunk into three equal segments, failing on one call."""
+
+    def __init__(self, fail_on_call: int = -1):
+        self.calls = []
+        self.fail_on_call = fail_on_call
+
+    def __call__(self, samples, language, prompt):
+        self.calls.append((len(samples) / SAMPLE_RATE, language, prompt))
+        if len(self.calls) - 1 == self.fail_on_call:
+            raise RuntimeError("CUDA out of memory")
+
+        third = len(samples) / SAMPLE_RATE / 3
+        return {
+            "language": "tr",
+            "segments": [
+                {"start": i * third, "end": (i + 1) * third, "text": f"part {i}", "words": []}
+                for i in range(3)
+            ],
+        }
+
+
+class TranscribeInChunksTest(unittest.TestCase):
+    def test_segments_are_streamed_chunk_by_chunk(self):
+        whisper = FakeWhisper()
+        events = []
+
+        language = transcribe_in_chunks(
+            silence(150),
+            whisper,
+            lambda segment: events.append(("segment", segment["start"], segment["end"])),
+            lambda position: events.append(("progress", position)),
+            initial_prompt="Toplantı",
+            chunk_seconds=60,
+        )
+
+        self.assertEqual(language, "tr")
+        # The last segment of a chunk is decoded again with the next one
+        self.assertEqual(events[:3], [("segment", 0.0, 20.0), ("segment", 20.0, 40.0), ("progress", 40.0)])
+        self.assertEqual(events[-1], ("progress", 150.0))
+        ends = [event[2] for event in events if event[0] == "segment"]
+        self.assertEqual(ends, sorted(ends))
+        self.assertAlmostEqual(ends[-1], 150.0)
+
+        # Later chunks keep the detected language and the previous text
+        self.assertEqual(whisper.calls[0], (60.0, None, "Toplantı"))
+        self.assertEqual(whisper.calls[1], (60.0, "tr", "Toplantı part 1"))
+
+    def test_failure_keeps_segments_before_it_for_a_resume(self):
+        segments = []
+        with self.assertRaises(RuntimeError):
+            transcribe_in_chunks(
+                silence(600),
+                FakeWhisper(fail_on_call=3),
+                segments.append,
+                lambda position: None,
+                chunk_seconds=60,
+            )
+
+        # Three chunks were decoded and sent before the failure
+        self.assertEqual(len(segments), 6)
+        resume_offset = segments[-1]["end"]
+        self.assertAlmostEqual(resume_offset, 120.0)
+
+        # The retry only transcribes what comes after the last segment
+        whisper = FakeWhisper()
+        resumed = []
+        transcribe_in_chunks(silence(600), whisper, resumed.append, lambda position: None,
+                             start_offset=resume_offset, chunk_seconds=60)
+        self.assertAlmostEqual(resumed[0]["start"], resume_offset)
+        self.assertAlmostEqual(resumed[-1]["end"], 600.0)
+
+    def test_chunks_without_speech_move_on(self):
+        progress = []
+        transcribe_in_chunks(
+            silence(130),
+            lambda samples, language, prompt: {"language": "en", "segments": []},
+            lambda segment: self.fail("no segments expected"),
+            progress.append,
+            chunk_seconds=60,
+        )
+        self.assertEqual(progress, [60.0, 120.0, 130.0])
+
+
+if __name__ == "__main__":
+    unittest.main()